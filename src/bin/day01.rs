@@ -52,8 +52,10 @@ fn gold(lists: &Lists) -> usize {
         let current = lists.left[i];
         let mut first = true;
 
+        // `skip_index` may move forward below, but only affects the next outer iteration
+        let start = skip_index;
         let mut in_other: usize = 0;
-        for j in skip_index..length {
+        for j in start..length {
             if lists.right[j] == current {
                 in_other += 1;
 
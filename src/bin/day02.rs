@@ -12,7 +12,7 @@ impl Levels {
     fn is_valid(&self, prev: usize, curr: usize) -> bool {
         // check adjacent level difference
         let diff = curr.abs_diff(prev);
-        if diff < 1 || diff > 3 {
+        if !(1..=3).contains(&diff) {
             return false
         }
 
@@ -1,6 +1,6 @@
 use std::io;
 
-use advent::read_input;
+use advent::{dot::Dot, flag_value, read_input};
 
 /// Order from string "before|after"
 #[derive(Debug)]
@@ -145,6 +145,15 @@ fn main() -> io::Result<()> {
     let input = read_input()?;
     let (orders, mut updates) = parse(&input);
 
+    if let Some(path) = flag_value("--dot") {
+        // page ordering rules as a graph, before -> after
+        let mut rules = Dot::digraph();
+        for order in &orders {
+            rules.add_edge(order.before, order.after, None);
+        }
+        rules.save(path)?;
+    }
+
     println!("silver: {}", silver(&orders, &updates));
     println!("gold: {}", gold(&orders, &mut updates));
 
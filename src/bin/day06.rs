@@ -1,233 +1,155 @@
-use std::{collections::HashSet, io};
+use std::io;
 
-use advent::{grid::Grid, read_input};
+use advent::{grid::{bits::BitGrid, cursor::{Cursor, Direction, Move}, frames::FrameRecorder, image::{Image, ImageExporter, Rgb}, journal::Journal, Grid, GridView, ParseGridError}, flag_value, read_input, tile_chars};
 
 #[derive(Debug, Clone, Copy)]
 enum Tile {
     Empty,
     Obstacle,
-    // special case for grabbing guard's initial position, handled separately
-    Guard((usize, usize, Direction))
 }
 
-impl Tile {
-    fn from_char(chr: char) -> Self {
-        match chr {
-            '#' => Self::Obstacle,
-            '.' => Self::Empty,
-            _ => panic!("invalid tile"),
-        }
-    }
-}
+tile_chars!(Tile {
+    Empty => '.',
+    Obstacle => '#',
+} markers {
+    // guard's initial position
+    '^' => Empty,
+});
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+#[derive(Debug)]
+struct Map {
+    grid: Grid<Tile>,
+    guard: Cursor,
 }
 
-impl Direction {
-    /// Get new direction when turning to the right
-    fn turn(self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up,
-            Self::Right => Self::Down,
-        }
-    }
-}
+fn parse(input: &str) -> Result<Map, ParseGridError> {
+    let (grid, markers) = Grid::parse_with_markers(input)?;
+    let pos = markers.get('^')?;
 
-#[derive(Debug, Clone, Copy)]
-struct Guard {
-    col: usize,
-    row: usize,
-    dir: Direction,
+    // assuming that all guards start facing up
+    Ok(Map { grid, guard: Cursor::new(pos, Direction::Up) })
 }
 
-#[derive(Debug)]
-struct Map {
-    grid: Grid<Tile>,
-    guard: Guard,
+/// Draw map with cells the guard has visited so far
+fn walk_image(frame: &Grid<(Tile, bool)>) -> Image {
+    let palette = |&(tile, visited): &(Tile, bool)| match (tile, visited) {
+        (Tile::Obstacle, _) => Rgb(128, 128, 128),
+        (Tile::Empty, true) => Rgb(13, 188, 121),
+        (Tile::Empty, false) => Rgb::BLACK,
+    };
+    ImageExporter::new(frame, palette)
+        .cell_size(4)
+        .render()
 }
 
-fn parse(input: &str) -> Map {
-    let mut grid: Grid<Tile> = Grid::new(input, |chr, (col, row)| {
-        match chr {
-            // assuming that all guards start facing up
-            '^' => Tile::Guard((col, row, Direction::Up)),
-            _ => Tile::from_char(chr)
-        }
+fn silver(map: &mut Map) -> io::Result<usize> {
+    let mut visited_coords = BitGrid::new(map.grid.width(), map.grid.height());
+    visited_coords.set(map.guard.pos.0, map.guard.pos.1);
+
+    // one frame per straight walk, single steps would make far too many frames
+    let mut recorder = FrameRecorder::from_flags()?;
+    let snapshot = |visited: &BitGrid| Grid::from_fn(visited.width(), visited.height(), |(col, row)| {
+        (map.grid.at(col, row).unwrap(), visited.test(col, row))
     });
 
-    // unfortunate limitation of grid implementation
-    // grab guard position manually and replace with empty tile
-    let mut guard: Option<Guard> = None;
-    for t in &mut grid.content {
-        match t {
-            Tile::Guard(pos) => {
-                guard = Some(Guard { col: pos.0, row: pos.1, dir: pos.2 });
-                *t = Tile::Empty
+    for walks in 0.. {
+        if let Some(recorder) = &mut recorder {
+            recorder.record_with(walks, || snapshot(&visited_coords));
+        }
+
+        // walk straight until an obstacle is in front of the guard
+        let mut walk = map.guard.entry(&map.grid)
+            .ray(map.guard.dir.offset())
+            .take_until(|tile| matches!(tile, Tile::Obstacle));
+
+        for (_, col, row) in walk.by_ref() {
+            visited_coords.set(col, row);
+        }
+
+        // guard walked out of bounds, we're done
+        if walk.blocked().is_none() {
+            if let Some(recorder) = &mut recorder {
+                recorder.record_with(walks + 1, || snapshot(&visited_coords));
             }
-            _ => {}
+            break
         }
-    };
 
-    Map { grid, guard: guard.unwrap() }
-} 
+        map.guard.pos = walk.last_free();
+        map.guard.turn_right();
+    }
 
-fn silver(map: &mut Map) -> usize {
-    let mut visited_coords: HashSet<(usize, usize)> = HashSet::new();
-    visited_coords.insert((map.guard.col, map.guard.row));
+    if let (Some(recorder), Some(path)) = (recorder, flag_value("--gif")) {
+        recorder.save_gif(path, 10, walk_image)?;
+    }
 
-    let mut entry = map.grid.entry(map.guard.col, map.guard.row);
-    let mut count = 1; // how many steps have we taken before hitting an obstacle
+    Ok(visited_coords.count())
+}
 
+/// Walk guard until she leaves the map, returns `false` if she gets stuck in a loop
+fn escapes(grid: &impl GridView<Tile>, mut guard: Cursor, turns: &mut [BitGrid; 4]) -> bool {
     loop {
-        // walking direction index
-        let walk_ind: (isize, isize) = match map.guard.dir {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        };
-
-        // Peek next tile in guard's path
-        match entry.offset(walk_ind.0*count, walk_ind.1*count) {
-            Some((Tile::Empty, col, row)) => {
-                count += 1;
-                visited_coords.insert((col, row));
-            },
-            Some((Tile::Obstacle, newcol, newrow)) => {
-                entry = map.grid.entry(
-                    // hack: get guard's position before the obstacle
-                    // this index is always valid
-                    newcol.checked_add_signed(-walk_ind.0).unwrap(),
-                    newrow.checked_add_signed(-walk_ind.1).unwrap(),
-                );
-
-                // turn guard and reset step counter
-                map.guard.dir = map.guard.dir.turn();
-                count = 1;
+        match guard.step_if(grid, |tile| matches!(tile, Tile::Empty)) {
+            Move::Moved(_) => (),
+            Move::Blocked(_) => {
+                // turning at the same spot in the same direction twice means a loop
+                if !turns[guard.dir as usize].set(guard.pos.0, guard.pos.1) {
+                    return false
+                }
+                guard.turn_right();
             },
-            None => break, // guard walked out of bounds, we're done
-            _ => panic!("guard tile needs to be removed"),
+            Move::LeftGrid => return true,
         }
     }
-
-    visited_coords.len()
 }
 
 fn gold(map: &mut Map) -> usize {
     // observations:
-    // - guard is in a loop if she returns to the inserted obstacle
-    // - obstacles can only be inserted in front of the guard
+    // - obstacles can only be inserted in front of the guard on her original path
+    // - only the first time she'd walk over a cell counts, guard would've been stopped earlier otherwise
 
-    let original_guard = map.guard;
+    let (width, height) = (map.grid.width(), map.grid.height());
+    let mut guard = map.guard;
     let mut ways = 0;
 
-    // positions of past inserted obstacles
-    let mut tried: HashSet<(usize, usize)> = HashSet::new();
+    // positions where an obstacle was already tried, including guard's start
+    let mut tried = BitGrid::new(width, height);
+    tried.set(guard.pos.0, guard.pos.1);
 
-    loop {
-        // position of obstacle inserted during this iteration
-        let mut obstacle: Option<(usize, usize, Direction)> = None;
-        // reset map
-        map.guard = original_guard;
-
-        // how many iterations ago was an obstacle inserted
-        let mut inserted_since: usize = 0;
-
-        // walk until out of bounds or loop is encountered
-        // each iteration either turns guard to the right
-        // or walks one step forward
-        loop {
-            let entry = map.grid.entry(map.guard.col, map.guard.row);
-            let walk_ind: (isize, isize) = match map.guard.dir {
-                Direction::Up => (0, -1),
-                Direction::Down => (0, 1),
-                Direction::Left => (-1, 0),
-                Direction::Right => (1, 0),
-            };
-            inserted_since += 1;
-
-            // catch scuffed loops
-            if inserted_since >= 10_000 {
-                ways += 1;
-                break
-            }
+    // turns taken per direction while checking one obstacle
+    let mut turns = [(); 4].map(|_| BitGrid::new(width, height));
 
-            // println!("{:?}", map.guard);
-
-            // Peek a tile in front the guard
-            match entry.offset(walk_ind.0, walk_ind.1) {
-                Some((Tile::Empty, col, row)) => {
-                    // There may be a inserted obstacle in front of us now
-                    // check if we need to turn because of it
-                    if let Some((obs_col, obs_row, orig_dir)) = obstacle {
-                        if obs_col == col && obs_row == row {
-                            // println!("virtual obstacle! turning");
-
-                            // check if we're looping
-                            if inserted_since > 1 && orig_dir == map.guard.dir {
-                                // println!("saw this virtual obstacle {} turns ago", inserted_since);
-                                ways += 1;
-                                break
-                            }
-
-                            map.guard.dir = map.guard.dir.turn();
-
-                            // moving forward will be handled next iter
-                            continue;
-                        }
-                    }
-
-                    // check if obstacle can be inserted here
-                    if obstacle.is_none() && !tried.contains(&(col, row)) {
-                        // we haven't tried to insert obstacle here yet
-                        // println!("inserted obstacle at ({}, {})", col, row);
-                        obstacle = Some((col, row, map.guard.dir));
-                        tried.insert((col, row));
-                        inserted_since = 0;
-                        continue;
+    let mut journal = Journal::new(&mut map.grid);
+    loop {
+        match guard.peek(&journal) {
+            Some((Tile::Empty, col, row)) => {
+                if tried.set(col, row) {
+                    // insert obstacle, see if guard gets stuck and remove it again
+                    let checkpoint = journal.checkpoint();
+                    journal.set(col, row, Tile::Obstacle);
+
+                    turns.iter_mut().for_each(BitGrid::reset);
+                    if !escapes(&journal, guard, &mut turns) {
+                        ways += 1;
                     }
 
-                    // Move guard forward
-                    map.guard.col = col;
-                    map.guard.row = row;
-                },
-                Some((Tile::Obstacle, _, _)) => {
-                    // println!("obstacle! turning");
-                    map.guard.dir = map.guard.dir.turn();
-                },
-                None => {
-                    // guard walked out of bounds
-                    // and no obstacle was inserted
-                    //
-                    // this is identical to exit condition in silver()
-                    if obstacle.is_none() {
-                        return ways;
-                    }
+                    journal.rollback(checkpoint);
+                }
 
-                    // obstacle was inserted, but guard walked out of bounds
-                    // try next obstacle position
-                    // println!("out of bounds!");
-                    break;
-                },
-                _ => panic!("guard tile needs to be removed"),
-            }
+                guard.step(&journal);
+            },
+            Some((Tile::Obstacle, _, _)) => guard.turn_right(),
+            // guard walked out of bounds on her original path
+            None => return ways,
         }
     }
 }
 
 fn main() -> io::Result<()> {
     let input = read_input()?;
-    let mut map = parse(&input);
+    let mut map = parse(&input)?;
     let original_guard = map.guard;
 
-    println!("silver: {}", silver(&mut map));
+    println!("silver: {}", silver(&mut map)?);
 
     // reset guard position
     map.guard = original_guard;
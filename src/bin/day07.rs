@@ -40,7 +40,7 @@ fn concat(a: usize, b: usize) -> usize {
     while b >= pow {
         pow *= 10;
     }
-    return a * pow + b;
+    a * pow + b
 }
 
 fn solve<const GOLD: bool>(equations: &[Equation]) -> usize {
@@ -57,7 +57,7 @@ fn solve<const GOLD: bool>(equations: &[Equation]) -> usize {
 
         while let Some((total, rem)) = stack.pop() {
             // found solution!
-            if rem.len() == 0 && total == eq.result {
+            if rem.is_empty() && total == eq.result {
                 result += eq.result;
                 break
             }
@@ -68,7 +68,7 @@ fn solve<const GOLD: bool>(equations: &[Equation]) -> usize {
             }
 
             // ran out of operands, this combination is not possible
-            if rem.len() == 0 {
+            if rem.is_empty() {
                 continue
             }
 
@@ -1,6 +1,6 @@
 use std::{collections::{HashMap, HashSet}, io};
 
-use advent::{grid::Grid, read_input};
+use advent::{grid::{Grid, TileChar}, read_input};
 
 #[derive(Debug, Clone, Copy)]
 enum Tile {
@@ -8,6 +8,24 @@ enum Tile {
     Antenna(char),
 }
 
+// antennas can be any letter or digit, so the legend can't be a fixed table
+impl TileChar for Tile {
+    fn from_char(chr: char) -> Option<Self> {
+        match chr {
+            '.' => Some(Tile::Empty),
+            c if c.is_ascii_alphanumeric() => Some(Tile::Antenna(c)),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Empty => '.',
+            Tile::Antenna(freq) => *freq,
+        }
+    }
+}
+
 fn solve(grid: &Grid<Tile>, gold: bool) -> usize {
     // Gather all (frequency, [position])'s into a hashmap
     let mut antennas: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
@@ -45,20 +63,19 @@ fn solve(grid: &Grid<Tile>, gold: bool) -> usize {
                 // - first->second
                 // - second->first
 
-                // in gold, keep adding antinodes until out of bounds
-                let mut n = if gold { 1 } else { 2 };
-                let mut m = if gold { 1 } else { 2 };
-
-                let entry = grid.entry(first.0, first.1);
-                while let Some((_, anti_col, anti_row)) = entry.offset(n*diff.0, n*diff.1) {
-                    antinodes.insert((anti_col, anti_row));
-                    if gold { n += 1; } else { break }
-                }
-
-                let entry = grid.entry(second.0, second.1);
-                while let Some((_, anti_col, anti_row)) = entry.offset(-(m*diff.0), -(m*diff.1)) {
-                    antinodes.insert((anti_col, anti_row));
-                    if gold { m += 1; } else { break }
+                // in gold, every cell along the line is an antinode
+                // in silver, only the one twice as far
+                let rays = [
+                    grid.entry(first.0, first.1).ray(diff),
+                    grid.entry(second.0, second.1).ray((-diff.0, -diff.1)),
+                ];
+
+                for mut ray in rays {
+                    if gold {
+                        antinodes.extend(ray.map(|(_, col, row)| (col, row)));
+                    } else if let Some((_, anti_col, anti_row)) = ray.nth(1) {
+                        antinodes.insert((anti_col, anti_row));
+                    }
                 }
 
                 // println!("{:?}", diff);
@@ -72,13 +89,7 @@ fn solve(grid: &Grid<Tile>, gold: bool) -> usize {
 
 fn main() -> io::Result<()> {
     let input = read_input()?;
-    let grid: Grid<Tile> = Grid::new(&input, |chr, _| {
-        match chr {
-            '.' => Tile::Empty,
-            c if c.is_ascii_alphanumeric() => Tile::Antenna(c),
-            _ => panic!("invalid tile!"),
-        }
-    });
+    let grid: Grid<Tile> = Grid::parse(&input)?;
 
     println!("silver: {}", solve(&grid, false));
     println!("gold: {}", solve(&grid, true));
@@ -1,27 +1,27 @@
-use std::{collections::{HashSet, VecDeque}, io};
+use std::{collections::VecDeque, io};
 
-use advent::{grid::Grid, read_input};
+use advent::{dot::Dot, flag_value, grid::{bits::BitGrid, Grid}, read_input};
 
 fn count_trailheads<const GOLD: bool>(grid: &Grid<u32>, start: (usize, usize)) -> usize {
     let mut score = 0;
 
     let mut queue = VecDeque::new();
     queue.push_front(start);
-    let mut explored = HashSet::new();
+    let mut explored = BitGrid::new(grid.width(), grid.height());
 
     while let Some((col, row)) = queue.pop_back() {
         // silver only: check if we're overlapping with some other trail.
         //
         // in gold this is allowed.
         // (note that `explored` still grows)
-        if explored.contains(&(col, row)) && !GOLD {
+        if explored.test(col, row) && !GOLD {
             continue
         }
 
-        explored.insert((col, row));
+        explored.set(col, row);
         let current = grid.entry(col, row);
         // unwrap here is ok since we only discover in-bounds points
-        let current_height = current.at_offset(0, 0).unwrap();
+        let current_height = *current.at_offset(0, 0).unwrap();
 
         // check for trailhead end
         if current_height == 9 {
@@ -34,7 +34,7 @@ fn count_trailheads<const GOLD: bool>(grid: &Grid<u32>, start: (usize, usize)) -
         for (dc, dr) in dirs {
             match current.offset(dc, dr) {
                 Some((next_height, next_col, next_row)) => {
-                    if next_height == current_height + 1 {
+                    if *next_height == current_height + 1 {
                         queue.push_front((next_col, next_row));
                     }
                 },
@@ -74,6 +74,31 @@ fn main() -> io::Result<()> {
     let input = read_input()?;
     let grid = Grid::new(&input, |c, _| c.to_digit(10).unwrap());
 
+    if let Some(path) = flag_value("--dot") {
+        // every uphill step of a trail, starting at trailheads
+        let trailheads = grid.iter_indexed().filter(|(_, &height)| height == 0).map(|(pos, _)| pos);
+        let trails = Dot::explore(trailheads, |&(col, row)| {
+            let current = grid.entry(col, row);
+            let height = grid.at(col, row).unwrap();
+
+            let dirs: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+            dirs.into_iter().filter_map(move |(dc, dr)| match current.offset(dc, dr) {
+                Some((&next, next_col, next_row)) if next == height + 1 => Some(((next_col, next_row), 1)),
+                _ => None,
+            })
+        });
+
+        // trailheads and summits stand out
+        let ends: Vec<(usize, usize)> = grid.iter_indexed()
+            .filter(|(_, &height)| height == 0 || height == 9)
+            .map(|(pos, _)| pos)
+            .collect();
+        trails
+            .label_nodes(|&(col, row)| format!("{col},{row}: {}", grid.at(col, row).unwrap()))
+            .highlight_nodes(&ends)
+            .save(path)?;
+    }
+
     println!("silver: {}", silver(&grid));
     println!("gold: {}", gold(&grid));
 
@@ -10,7 +10,7 @@ fn split_number(num: usize) -> (usize, usize) {
 }
 
 fn has_even_digits(num: usize) -> bool {
-    (num.ilog10() + 1) % 2 == 0
+    (num.ilog10() + 1).is_multiple_of(2)
 }
 
 fn blink(stones: &mut Vec<usize>) {
@@ -92,7 +92,7 @@ fn solve_machine(machine: &Machine) -> Option<usize> {
 fn solve(machines: &[Machine]) -> usize {
     let mut total = 0;
     for machine in machines {
-        if let Some(tokens) = solve_machine(&machine) {
+        if let Some(tokens) = solve_machine(machine) {
             total += tokens;
         }
     }
@@ -1,6 +1,6 @@
-use std::{fmt::Debug, io::{self, Read, Write}, str::FromStr};
+use std::{fmt::Debug, io::{self, Read}, path::{Path, PathBuf}, str::FromStr};
 
-use advent::read_input;
+use advent::{grid::{frames::FrameRecorder, image::{Image, ImageExporter, Rgb}, render::Renderer, Grid, GridView}, flag_value, read_input};
 
 #[derive(Debug)]
 struct Robot {
@@ -37,66 +37,91 @@ fn parse(input: &str) -> Vec<Robot> {
     robots
 }
 
-fn simulate_robots(robots: &mut [Robot], width: usize, height: usize) {
+/// Move robots one second forward, `room` keeps count of robots in each cell
+fn simulate_robots(robots: &mut [Robot], room: &mut Grid<usize>) {
     for robot in robots {
-        let (x, y) = (robot.pos.0 as isize, robot.pos.1 as isize);
-        let (vx, vy) = robot.vel;
+        // room is a torus, robots teleport to the other side at edges
+        let (col, row) = room.wrapping_entry(robot.pos.0, robot.pos.1)
+            .pos_at_offset(robot.vel.0, robot.vel.1)
+            .unwrap();
 
-        // calculate new position and find first positive modulo nx
-        // so that 0 <= nx < width
-        let nx = (x + vx).rem_euclid(width as isize);
-        let ny = (y + vy).rem_euclid(height as isize);
+        *room.at_mut(robot.pos.0, robot.pos.1).unwrap() -= 1;
+        *room.at_mut(col, row).unwrap() += 1;
 
-        robot.pos = (nx as usize, ny as usize);
+        robot.pos = (col, row);
     }
 }
 
 #[allow(dead_code)]
 fn pause() {
-    io::stdin().read(&mut [0]).unwrap();
+    io::stdin().read_exact(&mut [0]).unwrap();
 }
 
-fn print_robots(robots: &[Robot], width: usize, height: usize) {
-    // we'll be printing one character at a time,
-    // take stdout lock so it's fast
-    let mut lock = io::stdout().lock();
-    for row in 0..height {
-        for col in 0..width {
-            // unfortunate position check
-            let has_robot = robots.into_iter().any(|robot| robot.pos == (col, row));
-            let marker = if has_robot { "█" } else { " " }.as_bytes();
-
-            let _ = lock.write(marker);
-        }
-        let _ = lock.write(b"\n");
+fn print_robots(room: &Grid<usize>) {
+    let renderer = Renderer::with_legend(room, |&count| if count > 0 { '█' } else { ' ' });
+
+    let _ = renderer.print();
+    println!("\n");
+}
+
+/// Draw cells occupied by robots into an image
+fn robots_image(occupied: &Grid<bool>) -> Image {
+    let palette = |&occupied: &bool| if occupied { Rgb(13, 188, 121) } else { Rgb::BLACK };
+    ImageExporter::new(occupied, palette)
+        .cell_size(4)
+        .render()
+}
+
+/// Add step number to file name of an export path, `tree.png` becomes `tree_00042.png`
+fn numbered_path(path: &str, step: usize) -> PathBuf {
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{stem}_{step:05}.{}", ext.to_string_lossy())),
+        None => path.with_file_name(format!("{stem}_{step:05}")),
     }
-    let _ = lock.write(b"\n\n");
 }
 
 fn calculate_robot_variance(robots: &[Robot]) -> (f64, f64) {
     // theory: robots clumped together => small variance
     // calculate column and row variance separately
     let n = robots.len() as f64;
-    let pos_sum = robots.into_iter().fold((0, 0), |acc, robot| (acc.0 + robot.pos.0, acc.1 + robot.pos.1));
+    let pos_sum = robots.iter().fold((0, 0), |acc, robot| (acc.0 + robot.pos.0, acc.1 + robot.pos.1));
 
     let mean = (pos_sum.0 as f64 / n, pos_sum.1 as f64 / n);
-    let var = robots.into_iter().fold((0.0, 0.0), |acc, robot| {
+    let var = robots.iter().fold((0.0, 0.0), |acc, robot| {
         (
             acc.0 + (robot.pos.0 as f64 - mean.0)*(robot.pos.0 as f64 - mean.0),
             acc.1 + (robot.pos.1 as f64 - mean.1)*(robot.pos.1 as f64 - mean.1),
         )
     });
-    let var = (var.0 / (n-1.), var.1 / (n-1.));
 
-    var
+    (var.0 / (n-1.), var.1 / (n-1.))
 }
 
-fn solve<const GOLD: bool>(robots: &mut [Robot]) -> usize {
+fn solve<const GOLD: bool>(robots: &mut [Robot]) -> io::Result<usize> {
     let (width, height) = (101, 103);
     let total_seconds = if GOLD { 20_000 } else { 100 };
+    let export_path = flag_value("--export");
+    let mut recorder = FrameRecorder::from_flags()?;
+
+    let mut room: Grid<usize> = Grid::empty(width, height);
+    for robot in robots.iter() {
+        *room.at_mut(robot.pos.0, robot.pos.1).unwrap() += 1;
+    }
+
+    // only occupancy is kept per frame, robot counts would take 8 times the memory
+    if let Some(recorder) = &mut recorder {
+        recorder.record_with(0, || room.map(|&count| count > 0));
+    }
 
     for second in 0..total_seconds {
-        simulate_robots(robots, width, height);
+        simulate_robots(robots, &mut room);
+
+        if let Some(recorder) = &mut recorder {
+            recorder.record_with(second + 1, || room.map(|&count| count > 0));
+        }
 
         if GOLD {
             let (var_x, _var_y) = calculate_robot_variance(robots);
@@ -105,36 +130,38 @@ fn solve<const GOLD: bool>(robots: &mut [Robot]) -> usize {
             if var_x < 350. {
                 println!("suspicious x variance! var: {var_x}, second: {}", second + 1);
                 // visually see if this is a tree, just press ctrl-c when you see it
-                print_robots(robots, width, height);
+                print_robots(&room);
+
+                // there may be several candidates, so each gets its own file
+                if let Some(path) = &export_path {
+                    robots_image(&room.map(|&count| count > 0))
+                        .save(numbered_path(path, second + 1))?;
+                }
             }
         }
     }
 
-    // silver only
-    // find each robot's quadrant
-    let mut quads: [usize; 4] = [0, 0, 0, 0];
-
-    for robot in robots {
-        let (x, y) = robot.pos;
-        if x < width / 2 && y < height / 2 {
-            quads[0] += 1; // top-left
-        } else if x < width / 2 && y > height / 2 {
-            quads[1] += 1; // top-right
-        } else if x > width / 2 && y < height / 2 {
-            quads[2] += 1; // bottom-right
-        } else if x > width / 2 && y > height / 2 {
-            quads[3] += 1; // bottom-left
-        }
+    if let (Some(recorder), Some(path)) = (recorder, flag_value("--gif")) {
+        recorder.save_gif(path, 10, robots_image)?;
     }
 
-    quads.into_iter().product()
+    // silver only
+    // sum up robots in each quadrant
+    // middle row and column don't belong to any quadrant
+    let (half_w, half_h) = (width / 2, height / 2);
+    let quads = [(0, 0), (half_w + 1, 0), (0, half_h + 1), (half_w + 1, half_h + 1)].map(|(col, row)| {
+        let quad = room.sub_grid(col, row, half_w, half_h).unwrap();
+        quad.iter_indexed().map(|(_, &count)| count).sum::<usize>()
+    });
+
+    Ok(quads.into_iter().product())
 }
 
 fn main() -> io::Result<()> {
     let input = read_input()?;
     let mut robots = parse(&input);
 
-    println!("silver: {}", solve::</* false */true>(&mut robots));
+    println!("silver: {}", solve::</* false */true>(&mut robots)?);
     Ok(())
 }
 
@@ -1,165 +1,110 @@
-use std::{collections::{BinaryHeap, HashSet}, io};
+use std::io;
 
-use advent::{grid::Grid, read_input};
+use advent::{grid::{bits::BitGrid, cursor::Direction, image::{ImageExporter, Rgb}, render::{Color, Renderer, Style}, Grid}, flag_value, has_flag, read_input, search::{self, AllPaths}, tile_chars};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
-    Wall, Empty, Start, End,
+    Wall, Empty,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
-    North, South, East, West,
-}
-
-impl Dir {
-    /// Get a list of available directions from given facing
-    fn avail_dirs(&self) -> [Self; 3] {
-        match self {
-            Dir::North => [Dir::West, Dir::North, Dir::East],
-            Dir::South => [Dir::East, Dir::South, Dir::West],
-            Dir::East => [Dir::North, Dir::East, Dir::South],
-            Dir::West => [Dir::South, Dir::West, Dir::North],
-        }
-    }
-
-    fn as_offset(&self) -> (isize, isize) {
-        match self {
-            Dir::North => ( 0, -1),
-            Dir::South => ( 0,  1),
-            Dir::East =>  ( 1,  0),
-            Dir::West =>  (-1,  0),
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Node {
-    // cumulative cost until this node
-    cost: usize,
-    // metadata
-    pos: (usize, usize),
-    dir: Dir,
-    // gold only, list of all past coordinates visited on this path
-    past: Vec<(usize, usize)>,
-}
+tile_chars!(Tile {
+    Wall => '#',
+    Empty => '.',
+} markers {
+    'S' => Empty,
+    'E' => Empty,
+});
 
+/// Reindeer's position and facing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Visited {
+struct State {
     pos: (usize, usize),
-    dir: Dir,
+    dir: Direction,
 }
 
-impl From<&Node> for Visited {
-    fn from(value: &Node) -> Self {
-        Self { pos: value.pos, dir: value.dir }
-    }
-}
+impl State {
+    /// Possible moves from this state and their costs
+    fn successors(self, grid: &Grid<Tile>) -> impl Iterator<Item = (Self, usize)> + use<'_> {
+        let entry = grid.entry(self.pos.0, self.pos.1);
 
-// custom comparator for Node since it contains a lot of metadata
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // reversed order for min-heap
-        self.cost.cmp(&other.cost).reverse()
-    }
-}
+        // reindeer never turns around, that'd take two turns
+        [self.dir.turn_left(), self.dir, self.dir.turn_right()].into_iter().filter_map(move |dir| {
+            let (dc, dr) = dir.offset();
+            let (tile, col, row) = entry.offset(dc, dr)?;
+
+            if *tile == Tile::Wall {
+                return None
+            }
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+            if dir == self.dir {
+                Some((Self { pos: (col, row), dir }, 1))
+            } else {
+                // turning is only worth it if we can walk there afterwards
+                Some((Self { pos: self.pos, dir }, 1000))
+            }
+        })
     }
 }
 
-fn solve(grid: &Grid<Tile>) -> (usize, usize) {
-    // uniform cost search
-
-    let start = grid.find_one_pos_by(|it| it == Tile::Start).unwrap();
-
-    let node = Node { cost: 0, pos: start, dir: Dir::East, past: vec![start] };
-    let mut frontier: BinaryHeap<Node> = BinaryHeap::new();
-    let mut expanded: HashSet<Visited> = HashSet::new();
+/// Returns all optimal paths and set of tiles on any of them
+fn solve(grid: &Grid<Tile>, start: (usize, usize), end: (usize, usize)) -> (AllPaths<State>, BitGrid) {
+    let start = State { pos: start, dir: Direction::Right };
 
-    frontier.push(node);
+    let found = search::dijkstra_all(
+        start,
+        |state| state.successors(grid),
+        |state| state.pos == end,
+    ).unwrap();
 
-    let mut global_min = usize::MAX;
+    // same tile may be on a path multiple times with different facings
+    let mut tiles = BitGrid::new(grid.width(), grid.height());
+    for state in found.on_optimal_paths() {
+        tiles.set(state.pos.0, state.pos.1);
+    }
 
-    let mut paths = Vec::new();
-    while let Some(node) = frontier.pop() {
-        let curr = grid.entry(node.pos.0, node.pos.1);
+    (found, tiles)
+}
 
-        // reached end
-        if curr.at_offset(0, 0) == Some(Tile::End) {
-            // UCS will always find global minimum first?
-            if node.cost <= global_min {
-                global_min = node.cost;
-                paths.push(node); // record this node's path for the future
-            }
-            continue;
-        }
+fn main() -> io::Result<()> {
+    let input = read_input()?;
+    let (grid, markers) = Grid::<Tile>::parse_with_markers(&input)?;
+    let (start, end) = (markers.get('S')?, markers.get('E')?);
 
-        // mark current as visited
-        expanded.insert((&node).into());
+    let (found, best_tiles) = solve(&grid, start, end);
 
-        // discover next nodes
-        for next_dir in node.dir.avail_dirs() {
-            let (cost, moves) = if next_dir == node.dir {
-                (1, true)
-            } else {
-                (1000, false)
-            };
-
-            let (dc, dr) = next_dir.as_offset();
-
-            if let Some((next_tile, next_col, next_row)) = curr.offset(dc, dr) {
-                if next_tile == Tile::Empty || next_tile == Tile::End {
-                    // create new node for next iterations
-                    let newnode = Node {
-                        cost: node.cost + cost,
-                        pos: if moves { (next_col, next_row) } else { node.pos },
-                        dir: next_dir,
-                        past: {
-                            let mut cloned = node.past.clone();
-                            if moves {
-                                cloned.push((next_col, next_row));
-                            }
-                            cloned
-                        },
-                    };
-
-                    // expand frontier if we haven't been there before
-                    if !expanded.contains(&(&newnode).into()) {
-                        frontier.push(newnode);
-                    }
-                }
-            }
-        }
+    if has_flag("--render") {
+        Renderer::new(&grid)
+            .overlay(best_tiles.iter(), Style::glyph('O').with_color(Color::Green))
+            .mark([start], 'S')
+            .mark([end], 'E')
+            .print()?;
     }
 
-    // now, "paths" contains all paths with minimum cost with associated paths
-    // merge them all into a set and calculate number of unique coordinates
-    let mut uniq: HashSet<(usize, usize)> = HashSet::new();
-    for path in paths.into_iter() {
-        uniq.extend(path.past.into_iter());
+    if let Some(path) = flag_value("--export") {
+        let palette = |tile: &Tile| match tile {
+            Tile::Wall => Rgb(40, 40, 40),
+            Tile::Empty => Rgb::WHITE,
+        };
+
+        ImageExporter::new(&grid, palette)
+            .cell_size(4)
+            .overlay(best_tiles.iter(), Color::Green)
+            .overlay([start, end], Color::Red)
+            .save(path)?;
     }
 
-    (global_min, uniq.len())
-}
+    if let Some(path) = flag_value("--dot") {
+        // turning in place repeats a tile
+        let mut best_path: Vec<(usize, usize)> = found.paths(1)[0].iter().map(|state| state.pos).collect();
+        best_path.dedup();
 
-fn main() -> io::Result<()> {
-    let input = read_input()?;
-    let grid = Grid::new(&input, |chr, _| {
-        match chr {
-            '#' => Tile::Wall,
-            '.' => Tile::Empty,
-            'S' => Tile::Start,
-            'E' => Tile::End,
-            _ => panic!("invalid tile"),
-        }
-    });
-
-    let (silver, gold) = solve(&grid);
-    println!("silver: {}", silver);
-    println!("gold: {}", gold);
+        grid.to_dot(|tile| *tile == Tile::Empty, |_, _| None, |(col, row), _| format!("{col},{row}"))
+            .highlight_path(&best_path)
+            .save(path)?;
+    }
+
+    println!("silver: {}", found.cost);
+    println!("gold: {}", best_tiles.count());
 
     Ok(())
 }
@@ -90,11 +90,11 @@ impl Vm {
 
         match op {
             Op::Adv => {
-                self.a = self.a / 2_u64.pow(combo as u32);
+                self.a /= 2_u64.pow(combo as u32);
                 self.ip += 2;
             },
             Op::Bxl => {
-                self.b = self.b ^ literal;
+                self.b ^= literal;
                 self.ip += 2;
             },
             Op::Bst => {
@@ -106,7 +106,7 @@ impl Vm {
                 self.ip = literal;
             },
             Op::Bxc => {
-                self.b = self.b ^ self.c;
+                self.b ^= self.c;
                 self.ip += 2;
             },
             Op::Out => {
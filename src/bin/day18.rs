@@ -1,6 +1,6 @@
-use std::{collections::{BinaryHeap, HashSet}, io};
+use std::io;
 
-use advent::{grid::Grid, read_input};
+use advent::{grid::{frames::FrameRecorder, image::{Image, ImageExporter, Rgb}, Grid}, flag_value, read_input, search};
 
 #[derive(Debug, Clone, Copy, Default)]
 enum Tile {
@@ -10,16 +10,7 @@ enum Tile {
 }
 
 fn parse(input: &str, count: usize) -> Grid<Tile> {
-    let mut grid: Grid<Tile> = Grid::empty(71, 71);
-
-    let corruptor = corruptor(input);
-    for (col, row) in corruptor.take(count) {
-        if let Some(space) = grid.at_mut(col, row) {
-            *space = Tile::Corrupted;
-        }
-    }
-
-    grid
+    Grid::from_points(71, 71, corruptor(input).take(count), Tile::Corrupted)
 }
 
 // create an iterator that yields (col, row) pairs of corrupted coordinates
@@ -35,83 +26,63 @@ fn corruptor(input: &str) -> impl Iterator<Item = (usize, usize)> + use<'_> {
         })
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Node {
-    // position
-    pos: (usize, usize),
-    // current search cost (steps walked)
-    cost: usize,
-}
-
-impl PartialEq for Node {
-    fn eq(&self, other: &Self) -> bool {
-        self.cost == other.cost
-    }
-}
-impl Eq for Node {}
-
-// custom Ord implementation so that we can build a min-heap
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.cost.cmp(&other.cost).reverse()
-    }
-}
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 fn solve(grid: &Grid<Tile>) -> Option<usize> {
-    // basic uniform cost search
-    let mut frontier: BinaryHeap<Node> = BinaryHeap::new();
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    let dirs: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
 
-    let start = (0, 0);
-
-    frontier.push(Node { pos: start, cost: 0});
-
-    while let Some(node) = frontier.pop() {
-        if node.pos == (70, 70) {
-            return Some(node.cost)
-        }
-
-        visited.insert(node.pos);
-
-        let dirs: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-        let here = grid.entry(node.pos.0, node.pos.1);
-        for (dc, dr) in dirs {
-            let Some((Tile::Safe, col, row)) = here.offset(dc, dr) else { continue };
+    let successors = |&(col, row): &(usize, usize)| {
+        let here = grid.entry(col, row);
+        dirs.into_iter().filter_map(move |(dc, dr)| match here.offset(dc, dr) {
+            Some((Tile::Safe, col, row)) => Some(((col, row), 1)),
+            _ => None,
+        })
+    };
+    // manhattan distance to exit never overestimates
+    let heuristic = |&(col, row): &(usize, usize)| (70 - col) + (70 - row);
 
-            // very inefficient, need other data structure here
-            let in_frontier = frontier.iter().any(|node| node.pos == (col, row));
-            if !visited.contains(&(col, row)) && !in_frontier {
-                frontier.push(Node { pos: (col, row), cost: node.cost + 1 });
-            }
-        }
-    }
+    search::astar((0, 0), successors, heuristic, |&pos| pos == (70, 70))
+        .map(|found| found.cost)
+}
 
-    // frontier exhausted, no path
-    None
+/// Draw memory space with corrupted cells
+fn memory_image(grid: &Grid<Tile>) -> Image {
+    let palette = |tile: &Tile| match tile {
+        Tile::Safe => Rgb::BLACK,
+        Tile::Corrupted => Rgb(128, 128, 128),
+    };
+    ImageExporter::new(grid, palette)
+        .cell_size(4)
+        .render()
 }
 
-fn gold<I>(corruptor: I) -> (usize, usize)
+fn gold<I>(corruptor: I) -> io::Result<(usize, usize)>
 where
     I: Iterator<Item = (usize, usize)>
 {
     let mut grid: Grid<Tile> = Grid::empty(71, 71);
+    let mut recorder = FrameRecorder::from_flags()?;
 
-    for (corrupt_col, corrupt_row) in corruptor {
+    // frame for every fallen byte, the first one cutting off the exit included
+    let mut blocking = None;
+    for (fallen, (corrupt_col, corrupt_row)) in corruptor.enumerate() {
         if let Some(place) = grid.at_mut(corrupt_col, corrupt_row) {
             *place = Tile::Corrupted;
 
+            if let Some(recorder) = &mut recorder {
+                recorder.record(fallen + 1, &grid);
+            }
+
             if solve(&grid).is_none() {
-                return (corrupt_col, corrupt_row)
+                blocking = Some((corrupt_col, corrupt_row));
+                break
             }
         }
     }
 
-    unreachable!()
+    if let (Some(recorder), Some(path)) = (recorder, flag_value("--gif")) {
+        recorder.save_gif(path, 10, memory_image)?;
+    }
+
+    Ok(blocking.unwrap())
 }
 
 fn main() -> io::Result<()> {
@@ -119,7 +90,7 @@ fn main() -> io::Result<()> {
     let silver_grid = parse(&input, 1024);
 
     println!("silver: {:?}", solve(&silver_grid));
-    println!("gold: {:?}", gold(corruptor(&input)));
+    println!("gold: {:?}", gold(corruptor(&input))?);
 
     Ok(())
 }
@@ -22,9 +22,7 @@ fn tick(secret: u64) -> u64 {
     let secret = prune(secret);
 
     let secret = mix(secret * 2048, secret);
-    let secret = prune(secret);
-
-    secret
+    prune(secret)
 }
 
 fn main() -> io::Result<()> {
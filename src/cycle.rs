@@ -0,0 +1,135 @@
+//! Cycle detection for repeating simulations.
+//!
+//! Simulations are described by an initial state and a step function.
+//! Sequence of states must eventually repeat, otherwise detection never returns.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Cycle in a sequence of states
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Index of first state which is part of the cycle
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Map step number to the earliest step with identical state
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm, only keeps two states in memory
+pub fn brent<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // find cycle length by teleporting tortoise to hare at powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // hare is now `length` steps ahead, walk both until they meet at the start
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Floyd's tortoise and hare, only keeps two states in memory
+pub fn floyd<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // hare moves twice as fast, they meet somewhere inside the cycle
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let half = step(&hare);
+        hare = step(&half);
+    }
+
+    let mut start = 0;
+    let mut tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// State after `n` steps, skipping full cycles with [`brent`]
+pub fn state_after<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let steps = brent(initial.clone(), &mut step).reduce(n);
+    (0..steps).fold(initial, |state, _| step(&state))
+}
+
+/// Hash map based detection which remembers every state up to the cycle.
+///
+/// Steps each state only once and answers "state after n steps" without re-simulating,
+/// at the cost of storing all states.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl<S: Hash + Eq + Clone> History<S> {
+    pub fn detect(initial: S, mut step: impl FnMut(&S) -> S) -> Self {
+        let mut seen: HashMap<S, usize> = HashMap::new();
+        let mut states = Vec::new();
+        let mut state = initial;
+
+        loop {
+            if let Some(&start) = seen.get(&state) {
+                let cycle = Cycle { start, length: states.len() - start };
+                return Self { cycle, states }
+            }
+
+            let next = step(&state);
+            seen.insert(state.clone(), states.len());
+            states.push(state);
+            state = next;
+        }
+    }
+
+    /// State after `n` steps
+    pub fn state_after(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+
+    /// States before the cycle and one full cycle, in order
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
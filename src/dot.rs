@@ -0,0 +1,258 @@
+//! Graphviz DOT export for graphs and grids.
+//!
+//! Render the output with e.g. `dot -Tsvg graph.dot > graph.svg`,
+//! graphs made from grids keep their layout with `neato -n`.
+
+use std::{collections::{HashMap, VecDeque}, fmt, fs, hash::Hash, io, path::Path};
+
+use crate::grid::Grid;
+
+#[derive(Debug, Clone)]
+struct Node {
+    label: String,
+    /// Fixed position for layout engines which respect it
+    pos: Option<(usize, usize)>,
+    highlighted: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    from: usize,
+    to: usize,
+    cost: Option<usize>,
+    highlighted: bool,
+}
+
+/// Graph which can be written out in DOT format.
+///
+/// Nodes are identified by any hashable value and added on first use.
+#[derive(Debug, Clone)]
+pub struct Dot<N> {
+    directed: bool,
+    index: HashMap<N, usize>,
+    nodes: Vec<Node>,
+    edge_index: HashMap<(usize, usize), usize>,
+    edges: Vec<Edge>,
+}
+
+impl<N: Clone + Eq + Hash + fmt::Debug> Dot<N> {
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            index: HashMap::new(),
+            nodes: Vec::new(),
+            edge_index: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Empty directed graph
+    pub fn digraph() -> Self {
+        Self::new(true)
+    }
+
+    /// Empty undirected graph
+    pub fn graph() -> Self {
+        Self::new(false)
+    }
+
+    /// Directed graph of everything reachable from `starts`, with successors given like in [`crate::search`]
+    pub fn explore<I>(starts: impl IntoIterator<Item = N>, mut successors: impl FnMut(&N) -> I) -> Self
+    where
+        I: IntoIterator<Item = (N, usize)>,
+    {
+        let mut dot = Self::digraph();
+        let mut queue: VecDeque<N> = VecDeque::new();
+
+        for start in starts {
+            if !dot.index.contains_key(&start) {
+                dot.id(&start);
+                queue.push_back(start);
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            for (next, cost) in successors(&node) {
+                if !dot.index.contains_key(&next) {
+                    queue.push_back(next.clone());
+                }
+                dot.add_edge(node.clone(), next, Some(cost));
+            }
+        }
+
+        dot
+    }
+
+    /// Index of a node, adding it with its debug representation as label if it's new
+    fn id(&mut self, node: &N) -> usize {
+        if let Some(&id) = self.index.get(node) {
+            return id
+        }
+
+        let id = self.nodes.len();
+        self.index.insert(node.clone(), id);
+        self.nodes.push(Node { label: format!("{node:?}"), pos: None, highlighted: false });
+        id
+    }
+
+    /// Key of an edge, both directions share one in undirected graphs
+    fn edge_key(&self, from: usize, to: usize) -> (usize, usize) {
+        if self.directed { (from, to) } else { (from.min(to), from.max(to)) }
+    }
+
+    /// Add node or change label of existing one
+    pub fn add_node(&mut self, node: N, label: impl Into<String>) {
+        let id = self.id(&node);
+        self.nodes[id].label = label.into();
+    }
+
+    /// Add edge between two nodes, adding nodes as needed.
+    ///
+    /// Adding an existing edge only updates its cost.
+    pub fn add_edge(&mut self, from: N, to: N, cost: Option<usize>) {
+        let (from, to) = (self.id(&from), self.id(&to));
+        let key = self.edge_key(from, to);
+
+        match self.edge_index.get(&key) {
+            Some(&i) => self.edges[i].cost = cost,
+            None => {
+                self.edge_index.insert(key, self.edges.len());
+                self.edges.push(Edge { from, to, cost, highlighted: false });
+            },
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Relabel every node
+    pub fn label_nodes(mut self, label: impl Fn(&N) -> String) -> Self {
+        for (node, &id) in &self.index {
+            self.nodes[id].label = label(node);
+        }
+        self
+    }
+
+    /// Highlight given nodes, unknown ones are ignored
+    pub fn highlight_nodes<'a>(mut self, nodes: impl IntoIterator<Item = &'a N>) -> Self
+    where
+        N: 'a,
+    {
+        for node in nodes {
+            if let Some(&id) = self.index.get(node) {
+                self.nodes[id].highlighted = true;
+            }
+        }
+        self
+    }
+
+    /// Highlight nodes of a path, like [`SearchResult::path`](crate::search::SearchResult::path),
+    /// and edges between consecutive nodes
+    pub fn highlight_path<'a>(mut self, path: impl IntoIterator<Item = &'a N>) -> Self
+    where
+        N: 'a,
+    {
+        let mut prev: Option<usize> = None;
+        for node in path {
+            let Some(&id) = self.index.get(node) else {
+                prev = None;
+                continue
+            };
+            self.nodes[id].highlighted = true;
+
+            if let Some(prev) = prev {
+                if let Some(&i) = self.edge_index.get(&self.edge_key(prev, id)) {
+                    self.edges[i].highlighted = true;
+                }
+            }
+            prev = Some(id);
+        }
+        self
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl<N> fmt::Display for Dot<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (keyword, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(f, "{keyword} {{")?;
+
+        for (id, node) in self.nodes.iter().enumerate() {
+            write!(f, "    n{id} [label=\"{}\"", escape(&node.label))?;
+            if let Some((col, row)) = node.pos {
+                // dot's y axis points up
+                write!(f, ", pos=\"{},-{}!\"", col * 72, row * 72)?;
+            }
+            if node.highlighted {
+                write!(f, ", color=red, penwidth=2")?;
+            }
+            writeln!(f, "];")?;
+        }
+
+        for edge in &self.edges {
+            write!(f, "    n{} {arrow} n{}", edge.from, edge.to)?;
+
+            let mut attrs = Vec::new();
+            if let Some(cost) = edge.cost {
+                attrs.push(format!("label=\"{cost}\""));
+            }
+            if edge.highlighted {
+                attrs.push("color=red, penwidth=2".to_string());
+            }
+            if !attrs.is_empty() {
+                write!(f, " [{}]", attrs.join(", "))?;
+            }
+            writeln!(f, ";")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+/// Escape text for a quoted DOT string
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+impl<T> Grid<T> {
+    /// Undirected graph of passable cells connected to their passable 4-neighbors.
+    ///
+    /// Edges are labeled with `cost(from, to)`, `None` leaves an edge without cost.
+    /// Nodes are (col, row) positions pinned to their place in the grid.
+    pub fn to_dot(
+        &self,
+        passable: impl Fn(&T) -> bool,
+        cost: impl Fn(&T, &T) -> Option<usize>,
+        label: impl Fn((usize, usize), &T) -> String,
+    ) -> Dot<(usize, usize)> {
+        let mut dot = Dot::graph();
+
+        for (pos, thing) in self.iter_indexed() {
+            if !passable(thing) {
+                continue
+            }
+            dot.add_node(pos, label(pos, thing));
+            dot.nodes[dot.index[&pos]].pos = Some(pos);
+
+            // only look right and down, other edges were added from the other end
+            let entry = self.entry(pos.0, pos.1);
+            for (dc, dr) in [(1, 0), (0, 1)] {
+                if let Some((next, col, row)) = entry.offset(dc, dr) {
+                    if passable(next) {
+                        dot.add_edge(pos, (col, row), cost(thing, next));
+                    }
+                }
+            }
+        }
+
+        dot
+    }
+}
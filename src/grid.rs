@@ -1,11 +1,28 @@
-#[derive(Debug, Clone)]
+use std::{collections::HashMap, error::Error, fmt, io, marker::PhantomData, str::FromStr};
+
+pub mod bits;
+pub mod cursor;
+pub mod distance;
+pub mod frames;
+pub mod image;
+pub mod journal;
+pub mod neighborhood;
+pub mod pattern;
+pub mod ray;
+pub mod region;
+pub mod render;
+pub mod slice;
+pub mod sparse;
+pub mod transform;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Grid<T> {
     pub content: Vec<T>,
     width: usize,
     height: usize,
 }
 
-impl<T: Copy> Grid<T> {
+impl<T> Grid<T> {
     pub fn new(content: &str, transform: impl Fn(char, (usize, usize)) -> T) -> Self {
         // count number of lines (ignoring empty lines) and length with of first line
         let height = content.lines().filter(|line| !line.is_empty()).count();
@@ -27,30 +44,39 @@ impl<T: Copy> Grid<T> {
         }
     }
 
-    pub fn at(&self, col: usize, row: usize) -> Option<T> {
-        let index = col + row * self.width;
-        self.content.get(index).copied()
+    /// Create grid by calling `f` with every (col, row) position in row-major order
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut((usize, usize)) -> T) -> Self {
+        Self {
+            content: (0..width * height).map(|i| f((i % width, i / width))).collect(),
+            width,
+            height,
+        }
     }
 
-    pub fn at_mut(&mut self, col: usize, row: usize) -> Option<&mut T> {
-        let index = col + row * self.width;
-        self.content.get_mut(index)
+    pub fn width(&self) -> usize {
+        self.width
     }
 
-    pub fn entry(&self, col: usize, row: usize) -> GridEntry<'_, T> {
-        GridEntry { grid: self, col, row }
+    pub fn height(&self) -> usize {
+        self.height
     }
 
-    pub fn entry_mut(&mut self, col: usize, row: usize) -> GridEntryMut<'_, T> {
-        GridEntryMut { grid: self, col, row }
+    pub fn get(&self, col: usize, row: usize) -> Option<&T> {
+        if col >= self.width { return None }
+        self.content.get(col + row * self.width)
     }
 
-    pub fn width(&self) -> usize {
-        self.width
+    pub fn at_mut(&mut self, col: usize, row: usize) -> Option<&mut T> {
+        if col >= self.width { return None }
+        self.content.get_mut(col + row * self.width)
     }
 
-    pub fn height(&self) -> usize {
-        self.height
+    pub fn entry(&self, col: usize, row: usize) -> GridEntry<'_, T> {
+        GridEntry::new(self, col, row)
+    }
+
+    pub fn entry_mut(&mut self, col: usize, row: usize) -> GridEntryMut<'_, T> {
+        GridEntryMut { grid: self, col, row }
     }
 
     pub fn iter_indexed(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
@@ -64,13 +90,182 @@ impl<T: Copy> Grid<T> {
     /// Find position of one item by some predicate.
     ///
     /// Useful for finding starting positions etc..
-    pub fn find_one_pos_by(&self, pred: impl Fn(T) -> bool) -> Option<(usize, usize)> {
-        for (pos, &t) in self.iter_indexed() {
-            if pred(t) {
-                return Some(pos)
+    pub fn find_one_pos_by(&self, pred: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter_indexed()
+            .find(|(_, t)| pred(t))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            content: self.content.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Combine two grids of same size cell by cell.
+    ///
+    /// Panics if sizes differ.
+    pub fn zip_with<U, V>(&self, other: &Grid<U>, mut f: impl FnMut(&T, &U) -> V) -> Grid<V> {
+        assert_eq!((self.width, self.height), (other.width, other.height), "grid sizes differ");
+        Grid {
+            content: self.content.iter().zip(&other.content).map(|(a, b)| f(a, b)).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Pair up cells of two grids of same size.
+    ///
+    /// Panics if sizes differ.
+    pub fn zip<U: Clone>(&self, other: &Grid<U>) -> Grid<(T, U)> where T: Clone {
+        self.zip_with(other, |a, b| (a.clone(), b.clone()))
+    }
+
+    /// Split content into equally long non-empty lines and turn every character into a tile
+    fn parse_lines(
+        content: &str,
+        mut tile: impl FnMut(char, usize, usize) -> Result<T, ParseGridError>,
+    ) -> Result<Self, ParseGridError> {
+        let lines: Vec<&str> = content.lines()
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let height = lines.len();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+
+        let mut grid = Vec::with_capacity(width * height);
+        for (row, line) in lines.into_iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(ParseGridError::Ragged { row })
+            }
+
+            for (col, chr) in line.chars().enumerate() {
+                grid.push(tile(chr, col, row)?);
             }
         }
-        None
+
+        Ok(Self {
+            content: grid,
+            width,
+            height,
+        })
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    /// Copy of item, `None` if out of bounds
+    pub fn at(&self, col: usize, row: usize) -> Option<T> {
+        self.get(col, row).copied()
+    }
+}
+
+/// Read access to anything shaped like a grid, e.g. [`Grid`] itself or its views.
+///
+/// Provides the same `at`/`entry`/`iter_indexed` API as [`Grid`].
+pub trait GridView<T> {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    /// Returns reference to item, or `None` if out of bounds
+    fn get(&self, col: usize, row: usize) -> Option<&T>;
+
+    fn at(&self, col: usize, row: usize) -> Option<T> where T: Copy {
+        self.get(col, row).copied()
+    }
+
+    fn entry(&self, col: usize, row: usize) -> GridEntry<'_, T, Self> where Self: Sized {
+        GridEntry::new(self, col, row)
+    }
+
+    /// Entry whose offsets wrap around both edges, as if grid was a torus
+    fn wrapping_entry(&self, col: usize, row: usize) -> WrappingEntry<'_, T, Self> where Self: Sized {
+        WrappingEntry(GridEntry::new(self, col, row))
+    }
+
+    fn iter_indexed<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a T)> where T: 'a {
+        (0..self.height()).flat_map(move |row| {
+            (0..self.width()).map(move |col| ((col, row), self.get(col, row).unwrap()))
+        })
+    }
+
+    /// Copy view into a new grid
+    fn to_grid(&self) -> Grid<T> where T: Clone {
+        Grid {
+            content: self.iter_indexed().map(|(_, t)| t.clone()).collect(),
+            width: self.width(),
+            height: self.height(),
+        }
+    }
+}
+
+impl<T> GridView<T> for Grid<T> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, col: usize, row: usize) -> Option<&T> {
+        Grid::get(self, col, row)
+    }
+
+    fn iter_indexed<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a T)> where T: 'a {
+        Grid::iter_indexed(self)
+    }
+}
+
+impl<T: TileChar> Grid<T> {
+    /// Parse grid using tile's character legend.
+    ///
+    /// Marker characters aren't part of the legend and are rejected, see [`Grid::parse_with_markers`].
+    pub fn parse(content: &str) -> Result<Self, ParseGridError> {
+        Self::parse_lines(content, |chr, col, row| {
+            T::from_char(chr).ok_or(ParseGridError::InvalidChar { chr, col, row })
+        })
+    }
+
+    /// Parse grid and extract positions of marker characters.
+    ///
+    /// Markers (see [`TileChar::from_marker`]) are replaced with their underlying tile.
+    /// Each marker may appear only once.
+    pub fn parse_with_markers(content: &str) -> Result<(Self, Markers), ParseGridError> {
+        let mut markers = Markers::default();
+        let grid = Self::parse_lines(content, |chr, col, row| match T::from_marker(chr) {
+            Some(tile) => {
+                if let Some(first) = markers.0.insert(chr, (col, row)) {
+                    return Err(ParseGridError::DuplicateMarker { chr, first, second: (col, row) })
+                }
+                Ok(tile)
+            },
+            None => T::from_char(chr).ok_or(ParseGridError::InvalidChar { chr, col, row }),
+        })?;
+
+        Ok((grid, markers))
+    }
+}
+
+impl<T: TileChar> FromStr for Grid<T> {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<T: TileChar> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.content.chunks(self.width.max(1)) {
+            for tile in row {
+                write!(f, "{}", tile.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
 }
 
@@ -82,44 +277,118 @@ impl<T: Default + Clone> Grid<T> {
             height,
         }
     }
+
+    /// Create empty grid and set given points to `fill`, out of bounds points are ignored
+    pub fn from_points(width: usize, height: usize, points: impl IntoIterator<Item = (usize, usize)>, fill: T) -> Self {
+        let mut grid = Self::empty(width, height);
+        for (col, row) in points {
+            if let Some(item) = grid.at_mut(col, row) {
+                *item = fill.clone();
+            }
+        }
+        grid
+    }
+}
+
+// collect rows into a grid, panics if rows have different lengths
+impl<T, R: IntoIterator<Item = T>> FromIterator<R> for Grid<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut content = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for row in iter {
+            let before = content.len();
+            content.extend(row);
+
+            let row_width = content.len() - before;
+            assert_eq!(*width.get_or_insert(row_width), row_width, "row {height} has different length than first row");
+            height += 1;
+        }
+
+        Self {
+            content,
+            width: width.unwrap_or(0),
+            height,
+        }
+    }
 }
 
 /// Helper for working with offsets
 #[derive(Debug)]
-pub struct GridEntry<'a, T> {
-    grid: &'a Grid<T>,
+pub struct GridEntry<'a, T, G: ?Sized = Grid<T>> {
+    grid: &'a G,
     col: usize,
     row: usize,
+    tile: PhantomData<fn() -> T>,
 }
 
-// todo: get rid of Copy bound and make callers rely on .copied() ?
-impl<'a, T: Copy> GridEntry<'a, T> {
-    pub fn at_offset(&self, col_offset: isize, row_offset: isize) -> Option<T> {
+impl<'a, T, G: ?Sized> GridEntry<'a, T, G> {
+    fn new(grid: &'a G, col: usize, row: usize) -> Self {
+        Self { grid, col, row, tile: PhantomData }
+    }
+}
+
+impl<'a, T, G: GridView<T> + ?Sized> GridEntry<'a, T, G> {
+    pub fn at_offset(&self, col_offset: isize, row_offset: isize) -> Option<&'a T> {
         self.offset(col_offset, row_offset).map(|thing| thing.0)
     }
 
     /// Returns item at offset and its true column and row index if valid
-    pub fn offset(&self, col_offset: isize, row_offset: isize) -> Option<(T, usize, usize)> {
-        let Some(true_col) = self.col.checked_add_signed(col_offset)
-            else { return None };
-        let Some(true_row) = self.row.checked_add_signed(row_offset)
-            else { return None };
+    pub fn offset(&self, col_offset: isize, row_offset: isize) -> Option<(&'a T, usize, usize)> {
+        let true_col = self.col.checked_add_signed(col_offset)?;
+        let true_row = self.row.checked_add_signed(row_offset)?;
 
         if true_col >= self.grid.width() { return None }
         if true_row >= self.grid.height() { return None }
 
-        if let Some(thing) = self.grid.at(true_col, true_row) {
-            Some((thing, true_col, true_row))
-        } else {
-            None
+        self.grid.get(true_col, true_row).map(|thing| (thing, true_col, true_row))
+    }
+}
+
+impl<T: Copy, G: GridView<T> + ?Sized> GridEntry<'_, T, G> {
+    /// Copying version of [`GridEntry::offset`]
+    pub fn offset_copied(&self, col_offset: isize, row_offset: isize) -> Option<(T, usize, usize)> {
+        self.offset(col_offset, row_offset).map(|(&thing, col, row)| (thing, col, row))
+    }
+}
+
+/// Like [`GridEntry`], but offsets wrap around grid edges
+#[derive(Debug)]
+pub struct WrappingEntry<'a, T, G: ?Sized = Grid<T>>(GridEntry<'a, T, G>);
+
+impl<T, G: GridView<T> + ?Sized> WrappingEntry<'_, T, G> {
+    /// Returns wrapped column and row index at offset, `None` only if grid is empty
+    pub fn pos_at_offset(&self, col_offset: isize, row_offset: isize) -> Option<(usize, usize)> {
+        let (width, height) = (self.0.grid.width(), self.0.grid.height());
+        if width == 0 || height == 0 {
+            return None
         }
+
+        // first positive modulo so that 0 <= col < width
+        let col = (self.0.col as isize + col_offset).rem_euclid(width as isize);
+        let row = (self.0.row as isize + row_offset).rem_euclid(height as isize);
+
+        Some((col as usize, row as usize))
+    }
+}
+
+impl<'a, T, G: GridView<T> + ?Sized> WrappingEntry<'a, T, G> {
+    pub fn at_offset(&self, col_offset: isize, row_offset: isize) -> Option<&'a T> {
+        self.offset(col_offset, row_offset).map(|thing| thing.0)
+    }
+
+    /// Returns item at offset and its wrapped column and row index
+    pub fn offset(&self, col_offset: isize, row_offset: isize) -> Option<(&'a T, usize, usize)> {
+        let (col, row) = self.pos_at_offset(col_offset, row_offset)?;
+        self.0.grid.get(col, row).map(|thing| (thing, col, row))
     }
 }
 
 // allow converting mutable grid entry into immutable one
 impl<'a, T> From<GridEntryMut<'a, T>> for GridEntry<'a, T> {
     fn from(value: GridEntryMut<'a, T>) -> Self {
-        GridEntry { grid: value.grid, col: value.col, row: value.row }
+        GridEntry::new(value.grid, value.col, value.row)
     }
 }
 
@@ -129,25 +398,138 @@ pub struct GridEntryMut<'a, T> {
     row: usize,
 }
 
-impl<T: Copy> GridEntryMut<'_, T> {
+impl<T> GridEntryMut<'_, T> {
     pub fn at_offset_mut(&mut self, col_offset: isize, row_offset: isize) -> Option<&mut T> {
         self.offset_mut(col_offset, row_offset).map(|thing| thing.0)
     }
 
     /// Returns item at offset and its true column and row index if valid
     pub fn offset_mut(&mut self, col_offset: isize, row_offset: isize) -> Option<(&mut T, usize, usize)> {
-        let Some(true_col) = self.col.checked_add_signed(col_offset)
-            else { return None };
-        let Some(true_row) = self.row.checked_add_signed(row_offset)
-            else { return None };
+        // reborrow grid so that the item only borrows this entry
+        GridEntryMut { grid: &mut *self.grid, col: self.col, row: self.row }
+            .into_offset_mut(col_offset, row_offset)
+    }
+}
+
+impl<'a, T> GridEntryMut<'a, T> {
+    /// Like [`GridEntryMut::offset_mut`], but returned item borrows the grid instead of the entry
+    pub fn into_offset_mut(self, col_offset: isize, row_offset: isize) -> Option<(&'a mut T, usize, usize)> {
+        let true_col = self.col.checked_add_signed(col_offset)?;
+        let true_row = self.row.checked_add_signed(row_offset)?;
 
         if true_col >= self.grid.width() { return None }
         if true_row >= self.grid.height() { return None }
 
-        if let Some(thing) = self.grid.at_mut(true_col, true_row) {
-            Some((thing, true_col, true_row))
-        } else {
-            None
+        self.grid.at_mut(true_col, true_row).map(|thing| (thing, true_col, true_row))
+    }
+}
+
+/// Two-way mapping between a tile and the character representing it in puzzle input.
+///
+/// Enum tiles can implement this with [`tile_chars!`](crate::tile_chars).
+pub trait TileChar: Sized {
+    /// Returns tile represented by character, or `None` if character isn't part of legend
+    fn from_char(chr: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+
+    /// Returns underlying tile of a marker character, e.g. starting position on an empty tile.
+    ///
+    /// Markers are only recognized by [`Grid::parse_with_markers`], [`Grid::parse`] rejects them.
+    fn from_marker(_chr: char) -> Option<Self> {
+        None
+    }
+}
+
+/// Marker positions found by [`Grid::parse_with_markers`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markers(HashMap<char, (usize, usize)>);
+
+impl Markers {
+    /// Position of a marker, errors if it wasn't in the input
+    pub fn get(&self, chr: char) -> Result<(usize, usize), ParseGridError> {
+        self.0.get(&chr).copied().ok_or(ParseGridError::MissingMarker { chr })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (char, (usize, usize))> + '_ {
+        self.0.iter().map(|(&chr, &pos)| (chr, pos))
+    }
+}
+
+impl TileChar for char {
+    fn from_char(chr: char) -> Option<Self> {
+        Some(chr)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// Implements [`TileChar`] for a fieldless enum from a `Variant => 'c'` legend, e.g.
+/// `tile_chars!(Tile { Wall => '#', Empty => '.' });`
+///
+/// Markers can be given after the legend as `markers { 'S' => Empty }`.
+#[macro_export]
+macro_rules! tile_chars {
+    (
+        $tile:ty { $($variant:ident => $chr:literal),+ $(,)? }
+        $(markers { $($marker:literal => $under:ident),+ $(,)? })?
+    ) => {
+        impl $crate::grid::TileChar for $tile {
+            fn from_char(chr: char) -> Option<Self> {
+                match chr {
+                    $($chr => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    $(Self::$variant => $chr,)+
+                }
+            }
+
+            $(
+            fn from_marker(chr: char) -> Option<Self> {
+                match chr {
+                    $($marker => Some(Self::$under),)+
+                    _ => None,
+                }
+            }
+            )?
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// Character is not part of tile legend
+    InvalidChar { chr: char, col: usize, row: usize },
+    /// Row length differs from the first row
+    Ragged { row: usize },
+    /// Marker character appears more than once
+    DuplicateMarker { chr: char, first: (usize, usize), second: (usize, usize) },
+    /// Marker character doesn't appear at all
+    MissingMarker { chr: char },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChar { chr, col, row } => write!(f, "invalid tile {chr:?} at ({col}, {row})"),
+            Self::Ragged { row } => write!(f, "row {row} has different length than first row"),
+            Self::DuplicateMarker { chr, first, second } => write!(f, "marker {chr:?} at both {first:?} and {second:?}"),
+            Self::MissingMarker { chr } => write!(f, "marker {chr:?} not found"),
         }
     }
 }
+
+impl Error for ParseGridError {}
+
+// allow using `?` in main
+impl From<ParseGridError> for io::Error {
+    fn from(value: ParseGridError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
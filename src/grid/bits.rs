@@ -0,0 +1,189 @@
+//! Bit-packed boolean grid, mostly for visited sets.
+
+use std::fmt;
+
+const BITS: usize = u64::BITS as usize;
+
+/// Grid of booleans packed into 64-bit words.
+///
+/// Uses the same (col, row) coordinates as [`Grid`](super::Grid),
+/// out of bounds positions are ignored and always read as unset.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    /// Create grid with every bit cleared
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            words: vec![0; (width * height).div_ceil(BITS)],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Word index and bit mask of position
+    fn locate(&self, col: usize, row: usize) -> Option<(usize, u64)> {
+        if col >= self.width || row >= self.height {
+            return None
+        }
+        let index = col + row * self.width;
+        Some((index / BITS, 1 << (index % BITS)))
+    }
+
+    pub fn test(&self, col: usize, row: usize) -> bool {
+        self.locate(col, row)
+            .is_some_and(|(word, mask)| self.words[word] & mask != 0)
+    }
+
+    /// Set bit, returns `true` if it wasn't set before.
+    ///
+    /// Out of bounds positions are ignored and return `false`.
+    pub fn set(&mut self, col: usize, row: usize) -> bool {
+        let Some((word, mask)) = self.locate(col, row) else { return false };
+
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] |= mask;
+        !was_set
+    }
+
+    /// Clear bit, returns `true` if it was set before
+    pub fn clear(&mut self, col: usize, row: usize) -> bool {
+        let Some((word, mask)) = self.locate(col, row) else { return false };
+
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    /// Clear every bit
+    pub fn reset(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Number of set bits
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Set every bit which is set in other grid of same size
+    pub fn union_with(&mut self, other: &Self) {
+        assert_eq!((self.width, self.height), (other.width, other.height), "grid sizes differ");
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Clear every bit which isn't set in other grid of same size
+    pub fn intersect_with(&mut self, other: &Self) {
+        assert_eq!((self.width, self.height), (other.width, other.height), "grid sizes differ");
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    /// Iterate positions of set bits in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            // pop lowest set bit until word is empty
+            std::iter::successors(Some(word), |&word| Some(word & word.wrapping_sub(1)))
+                .take_while(|&word| word != 0)
+                .map(move |word| {
+                    let index = i * BITS + word.trailing_zeros() as usize;
+                    (index % self.width, index / self.width)
+                })
+        })
+    }
+}
+
+// print like a grid instead of a list of words
+impl fmt::Debug for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "BitGrid {}x{}", self.width, self.height)?;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                write!(f, "{}", if self.test(col, row) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_test_clear() {
+        // 130 bits span three words
+        let mut bits = BitGrid::new(13, 10);
+
+        assert!(bits.set(0, 0));
+        assert!(!bits.set(0, 0));
+        assert!(bits.set(12, 4));
+        assert!(bits.set(12, 9));
+        assert!(!bits.set(13, 0));
+        assert!(!bits.set(0, 10));
+
+        assert!(bits.test(12, 4) && bits.test(12, 9));
+        assert!(!bits.test(11, 4) && !bits.test(13, 0));
+        assert_eq!(bits.count(), 3);
+
+        assert!(bits.clear(12, 4));
+        assert!(!bits.clear(12, 4));
+        assert!(!bits.clear(20, 20));
+        assert_eq!(bits.count(), 2);
+
+        bits.reset();
+        assert_eq!(bits.count(), 0);
+    }
+
+    #[test]
+    fn iter_in_row_major_order() {
+        let mut bits = BitGrid::new(13, 10);
+        let positions = [(0, 0), (5, 0), (12, 4), (0, 5), (11, 9), (12, 9)];
+        for (col, row) in positions.iter().rev() {
+            bits.set(*col, *row);
+        }
+
+        assert_eq!(bits.iter().collect::<Vec<_>>(), positions);
+        assert_eq!(BitGrid::new(0, 0).iter().count(), 0);
+    }
+
+    #[test]
+    fn set_operations() {
+        let mut a = BitGrid::new(70, 1);
+        let mut b = BitGrid::new(70, 1);
+        for col in [1, 2, 65] {
+            a.set(col, 0);
+        }
+        for col in [2, 65, 69] {
+            b.set(col, 0);
+        }
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.iter().map(|(col, _)| col).collect::<Vec<_>>(), [1, 2, 65, 69]);
+
+        a.intersect_with(&b);
+        assert_eq!(a.iter().map(|(col, _)| col).collect::<Vec<_>>(), [2, 65]);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid sizes differ")]
+    fn size_mismatch() {
+        BitGrid::new(3, 2).union_with(&BitGrid::new(2, 3));
+    }
+}
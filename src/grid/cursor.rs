@@ -0,0 +1,147 @@
+//! Position and facing for walking around a grid.
+
+use super::{GridEntry, GridView};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// Column and row offset of one step
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Self::Up => (0, -1),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+            Self::Right => (1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Left => Self::Down,
+            Self::Down => Self::Right,
+            Self::Right => Self::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Parse `^v<>` arrows
+    pub fn from_arrow(chr: char) -> Option<Self> {
+        match chr {
+            '^' => Some(Self::Up),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+/// What happened when cursor tried to step forward
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move<'a, T> {
+    /// Cursor moved onto this cell
+    Moved(&'a T),
+    /// Cell in front didn't pass the check, cursor stayed in place
+    Blocked(&'a T),
+    /// Step would have left the grid, cursor stayed in place
+    LeftGrid,
+}
+
+impl<T> Move<'_, T> {
+    pub fn moved(&self) -> bool {
+        matches!(self, Move::Moved(_))
+    }
+}
+
+/// Position and direction on a grid.
+///
+/// Cursor doesn't borrow the grid, so grid can be modified between moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    pub pos: (usize, usize),
+    pub dir: Direction,
+}
+
+impl Cursor {
+    pub fn new(pos: (usize, usize), dir: Direction) -> Self {
+        Self { pos, dir }
+    }
+
+    /// Position one step forward if it's inside the grid
+    pub fn pos_ahead<T>(&self, grid: &(impl GridView<T> + ?Sized)) -> Option<(usize, usize)> {
+        let (col_offset, row_offset) = self.dir.offset();
+        let col = self.pos.0.checked_add_signed(col_offset)?;
+        let row = self.pos.1.checked_add_signed(row_offset)?;
+
+        if col >= grid.width() { return None }
+        if row >= grid.height() { return None }
+
+        Some((col, row))
+    }
+
+    /// Returns cell in front of the cursor and its column and row index
+    pub fn peek<'a, T>(&self, grid: &'a (impl GridView<T> + ?Sized)) -> Option<(&'a T, usize, usize)> {
+        let (col, row) = self.pos_ahead(grid)?;
+        grid.get(col, row).map(|thing| (thing, col, row))
+    }
+
+    /// Step forward unless it would leave the grid
+    pub fn step<'a, T>(&mut self, grid: &'a (impl GridView<T> + ?Sized)) -> Move<'a, T> {
+        self.step_if(grid, |_| true)
+    }
+
+    /// Step forward if `passable` accepts the cell in front
+    pub fn step_if<'a, T>(&mut self, grid: &'a (impl GridView<T> + ?Sized), passable: impl FnOnce(&T) -> bool) -> Move<'a, T> {
+        match self.peek(grid) {
+            Some((thing, col, row)) if passable(thing) => {
+                self.pos = (col, row);
+                Move::Moved(thing)
+            },
+            Some((thing, _, _)) => Move::Blocked(thing),
+            None => Move::LeftGrid,
+        }
+    }
+
+    pub fn turn_right(&mut self) {
+        self.dir = self.dir.turn_right();
+    }
+
+    pub fn turn_left(&mut self) {
+        self.dir = self.dir.turn_left();
+    }
+
+    pub fn turn_around(&mut self) {
+        self.dir = self.dir.reverse();
+    }
+
+    /// Entry at cursor's position
+    pub fn entry<'a, T, G: GridView<T> + ?Sized>(&self, grid: &'a G) -> GridEntry<'a, T, G> {
+        GridEntry::new(grid, self.pos.0, self.pos.1)
+    }
+}
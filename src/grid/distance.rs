@@ -0,0 +1,149 @@
+//! Distance fields from one or more sources.
+
+use std::collections::VecDeque;
+
+use crate::search::queue::IndexedHeap;
+
+use super::{region::ORTHOGONAL, Grid};
+
+/// Distances to nearest source and which source that is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField {
+    /// Distance to nearest source, `None` if unreachable
+    pub distances: Grid<Option<u32>>,
+    /// Index of nearest source in the order sources were given, ties go to lower index
+    pub nearest: Grid<Option<usize>>,
+}
+
+impl DistanceField {
+    fn new(width: usize, height: usize) -> Self {
+        Self { distances: Grid::empty(width, height), nearest: Grid::empty(width, height) }
+    }
+
+    pub fn distance(&self, col: usize, row: usize) -> Option<u32> {
+        self.distances.get(col, row).copied().flatten()
+    }
+
+    pub fn nearest(&self, col: usize, row: usize) -> Option<usize> {
+        self.nearest.get(col, row).copied().flatten()
+    }
+}
+
+impl<T> Grid<T> {
+    /// Steps from nearest source to every cell over 4-connected passable cells.
+    ///
+    /// Sources count as reachable even if they aren't passable, out of bounds sources are ignored.
+    pub fn bfs_distances(
+        &self,
+        sources: impl IntoIterator<Item = (usize, usize)>,
+        passable: impl Fn(&T) -> bool,
+    ) -> Grid<Option<u32>> {
+        self.bfs_field(sources, passable).distances
+    }
+
+    /// Like [`Grid::bfs_distances`], but also labels each cell with its nearest source
+    pub fn bfs_field(
+        &self,
+        sources: impl IntoIterator<Item = (usize, usize)>,
+        passable: impl Fn(&T) -> bool,
+    ) -> DistanceField {
+        let mut field = DistanceField::new(self.width, self.height);
+        let mut queue = VecDeque::new();
+
+        for (label, (col, row)) in sources.into_iter().enumerate() {
+            if col >= self.width || row >= self.height {
+                continue
+            }
+            let i = col + row * self.width;
+            if field.distances.content[i].is_some() {
+                continue
+            }
+            field.distances.content[i] = Some(0);
+            field.nearest.content[i] = Some(label);
+            queue.push_back((col, row));
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            let i = pos.0 + pos.1 * self.width;
+            let (dist, label) = (field.distances.content[i].unwrap() + 1, field.nearest.content[i]);
+
+            for dir in ORTHOGONAL {
+                let Some(next) = self.neighbor(pos, dir) else { continue };
+                let j = next.0 + next.1 * self.width;
+
+                match field.distances.content[j] {
+                    // cells at the next level can still switch to a lower labeled source
+                    Some(seen) if seen == dist => {
+                        field.nearest.content[j] = field.nearest.content[j].min(label);
+                    },
+                    Some(_) => (),
+                    None if passable(&self.content[j]) => {
+                        field.distances.content[j] = Some(dist);
+                        field.nearest.content[j] = label;
+                        queue.push_back(next);
+                    },
+                    None => (),
+                }
+            }
+        }
+
+        field
+    }
+
+    /// Weighted version of [`Grid::bfs_distances`], `cost` of entering a cell is `None` if it's impassable
+    pub fn dijkstra_distances(
+        &self,
+        sources: impl IntoIterator<Item = (usize, usize)>,
+        cost: impl Fn(&T) -> Option<u32>,
+    ) -> Grid<Option<u32>> {
+        self.dijkstra_field(sources, cost).distances
+    }
+
+    /// Like [`Grid::dijkstra_distances`], but also labels each cell with its nearest source
+    pub fn dijkstra_field(
+        &self,
+        sources: impl IntoIterator<Item = (usize, usize)>,
+        cost: impl Fn(&T) -> Option<u32>,
+    ) -> DistanceField {
+        let mut field = DistanceField::new(self.width, self.height);
+
+        // best known (distance, label) for every cell, label breaks ties
+        let mut best: Vec<Option<(u32, usize)>> = vec![None; self.content.len()];
+        let mut frontier = IndexedHeap::new();
+
+        for (label, (col, row)) in sources.into_iter().enumerate() {
+            if col >= self.width || row >= self.height {
+                continue
+            }
+            let i = col + row * self.width;
+            if best[i].is_some() {
+                continue
+            }
+            best[i] = Some((0, label));
+            frontier.push((col, row), (0, label));
+        }
+
+        while let Some((pos, (dist, label))) = frontier.pop() {
+            let i = pos.0 + pos.1 * self.width;
+            field.distances.content[i] = Some(dist);
+            field.nearest.content[i] = Some(label);
+
+            for dir in ORTHOGONAL {
+                let Some(next) = self.neighbor(pos, dir) else { continue };
+                let j = next.0 + next.1 * self.width;
+                let Some(step) = cost(&self.content[j]) else { continue };
+                // distances past u32::MAX are treated as unreachable
+                let Some(next_dist) = dist.checked_add(step) else { continue };
+
+                let candidate = (next_dist, label);
+                if best[j].is_some_and(|known| known <= candidate) {
+                    continue
+                }
+                best[j] = Some(candidate);
+                frontier.push(next, candidate);
+            }
+        }
+
+        field
+    }
+}
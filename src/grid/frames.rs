@@ -0,0 +1,464 @@
+//! Recording of simulation frames.
+//!
+//! Frames are captured as [`Grid`] snapshots and only rendered into [`Image`]s
+//! when they're written out as a numbered image sequence or a single animated GIF.
+
+use std::{collections::HashMap, fs::File, io::{self, BufWriter, Write}, ops::{Bound, RangeBounds}, path::Path};
+
+use crate::flag_value;
+
+use super::{image::{Image, Rgb}, Grid};
+
+/// Collects frames of a step-by-step simulation.
+///
+/// Steps outside of the configured range or not landing on `every`-th step are skipped,
+/// see [`FrameRecorder::wants`].
+#[derive(Debug, Clone)]
+pub struct FrameRecorder<T> {
+    frames: Vec<(usize, Grid<T>)>,
+    every: usize,
+    start: Bound<usize>,
+    end: Bound<usize>,
+}
+
+impl<T> Default for FrameRecorder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FrameRecorder<T> {
+    pub fn new() -> Self {
+        Self {
+            frames: Vec::new(),
+            every: 1,
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+        }
+    }
+
+    /// Recorder configured from command line if `--gif` was given.
+    ///
+    /// `--steps a..b` and `--every n` narrow down frames.
+    pub fn from_flags() -> io::Result<Option<Self>> {
+        if flag_value("--gif").is_none() {
+            return Ok(None)
+        }
+
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+
+        let mut recorder = Self::new();
+        if let Some(steps) = flag_value("--steps") {
+            let range = steps.split_once("..")
+                .and_then(|(start, end)| Some(start.parse::<usize>().ok()?..end.parse::<usize>().ok()?))
+                .ok_or_else(|| invalid(format!("--steps should be given as a..b, got {steps:?}")))?;
+            recorder = recorder.steps(range);
+        }
+        if let Some(every) = flag_value("--every") {
+            let every = every.parse()
+                .map_err(|_| invalid(format!("--every should be a number, got {every:?}")))?;
+            recorder = recorder.every(every);
+        }
+
+        Ok(Some(recorder))
+    }
+
+    /// Only keep every `n`th step
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// Only keep steps within given range
+    pub fn steps(mut self, range: impl RangeBounds<usize>) -> Self {
+        self.start = range.start_bound().cloned();
+        self.end = range.end_bound().cloned();
+        self
+    }
+
+    /// Check if a frame of this step would be recorded.
+    ///
+    /// Useful for skipping expensive rendering.
+    pub fn wants(&self, step: usize) -> bool {
+        // counting starts at first step of the range
+        let first = match self.start {
+            Bound::Included(start) => start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        (self.start, self.end).contains(&step) && (step - first).is_multiple_of(self.every)
+    }
+
+    /// Record a snapshot of the grid if step passes the filters
+    pub fn record(&mut self, step: usize, grid: &Grid<T>)
+    where
+        T: Clone,
+    {
+        if self.wants(step) {
+            self.frames.push((step, grid.clone()));
+        }
+    }
+
+    /// Like [`FrameRecorder::record`], but snapshot is only made when it's needed
+    pub fn record_with(&mut self, step: usize, snapshot: impl FnOnce() -> Grid<T>) {
+        if self.wants(step) {
+            self.frames.push((step, snapshot()));
+        }
+    }
+
+    /// Recorded snapshots and their steps
+    pub fn frames(&self) -> &[(usize, Grid<T>)] {
+        &self.frames
+    }
+
+    /// Render every frame into `dir` as `{prefix}_{step}.{extension}`
+    pub fn save_sequence(
+        &self,
+        dir: impl AsRef<Path>,
+        prefix: &str,
+        extension: &str,
+        render: impl Fn(&Grid<T>) -> Image,
+    ) -> io::Result<()> {
+        let dir = dir.as_ref();
+        for (step, frame) in &self.frames {
+            render(frame).save(dir.join(format!("{prefix}_{step:05}.{extension}")))?;
+        }
+        Ok(())
+    }
+
+    /// Render frames into an endlessly looping GIF with `delay` hundredths of a second between frames.
+    ///
+    /// All rendered frames need to be same size and share at most 256 colors between them.
+    pub fn save_gif(&self, path: impl AsRef<Path>, delay: u16, render: impl Fn(&Grid<T>) -> Image) -> io::Result<()> {
+        self.write_gif(BufWriter::new(File::create(path)?), delay, render)
+    }
+
+    /// Like [`FrameRecorder::save_gif`], but writes into any writer.
+    ///
+    /// Frames are rendered one at a time, once for collecting colors and once for encoding.
+    pub fn write_gif(&self, mut out: impl Write, delay: u16, render: impl Fn(&Grid<T>) -> Image) -> io::Result<()> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_string());
+
+        let Some((_, first)) = self.frames.first()
+            else { return Err(invalid("no frames recorded")) };
+        let first = render(first);
+        let (width, height) = (first.width(), first.height());
+
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(invalid("frames are too large for GIF"))
+        }
+
+        // build global palette shared by all frames
+        let mut palette: Vec<Rgb> = Vec::new();
+        let mut lookup: HashMap<Rgb, u8> = HashMap::new();
+        for (_, frame) in &self.frames {
+            let frame = render(frame);
+            if frame.width() != width || frame.height() != height {
+                return Err(invalid("frames have different sizes"))
+            }
+
+            for &color in frame.pixels() {
+                if lookup.contains_key(&color) {
+                    continue
+                }
+                if palette.len() == 256 {
+                    return Err(invalid("frames have more than 256 colors"))
+                }
+                lookup.insert(color, palette.len() as u8);
+                palette.push(color);
+            }
+        }
+
+        // color table size is 2^(bits), at least 2 entries
+        let bits = (palette.len().max(2).next_power_of_two().trailing_zeros()) as u8;
+        palette.resize(1 << bits, Rgb::BLACK);
+
+        out.write_all(b"GIF89a")?;
+        out.write_all(&(width as u16).to_le_bytes())?;
+        out.write_all(&(height as u16).to_le_bytes())?;
+        // global color table present, 8-bit color resolution
+        out.write_all(&[0xf0 | (bits - 1), 0, 0])?;
+        for &Rgb(r, g, b) in &palette {
+            out.write_all(&[r, g, b])?;
+        }
+
+        // netscape extension, loop forever
+        out.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+        let min_code_size = bits.max(2);
+        for (_, frame) in &self.frames {
+            let frame = render(frame);
+
+            // graphic control extension with frame delay
+            out.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+            out.write_all(&delay.to_le_bytes())?;
+            out.write_all(&[0x00, 0x00])?;
+
+            // image descriptor covering the whole screen, no local color table
+            out.write_all(&[0x2c, 0, 0, 0, 0])?;
+            out.write_all(&(width as u16).to_le_bytes())?;
+            out.write_all(&(height as u16).to_le_bytes())?;
+            out.write_all(&[0x00])?;
+
+            let indices: Vec<u8> = frame.pixels().iter().map(|color| lookup[color]).collect();
+            out.write_all(&[min_code_size])?;
+            for block in lzw_encode(&indices, min_code_size).chunks(255) {
+                out.write_all(&[block.len() as u8])?;
+                out.write_all(block)?;
+            }
+            out.write_all(&[0x00])?;
+        }
+
+        out.write_all(&[0x3b])?;
+        out.flush()
+    }
+}
+
+/// Packs variable width codes into bytes, least significant bit first
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    len: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.acc |= (code as u32) << self.len;
+        self.len += width;
+        while self.len >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+/// GIF flavored LZW compression
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4095;
+
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut out = BitWriter { bytes: Vec::new(), acc: 0, len: 0 };
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut width = min_code_size + 1;
+
+    out.write(clear, width);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        out.write(end, width);
+        return out.finish()
+    };
+
+    let mut prefix = first as u16;
+    for &index in rest {
+        if let Some(&code) = dict.get(&(prefix, index)) {
+            prefix = code;
+            continue
+        }
+
+        out.write(prefix, width);
+
+        if next == MAX_CODE {
+            // dictionary is full, start over
+            out.write(clear, width);
+            dict.clear();
+            next = end + 1;
+            width = min_code_size + 1;
+        } else {
+            if next >= 1 << width {
+                width += 1;
+            }
+            dict.insert((prefix, index), next);
+            next += 1;
+        }
+
+        prefix = index as u16;
+    }
+
+    out.write(prefix, width);
+    out.write(end, width);
+    out.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::image::ImageExporter;
+
+    /// Reads codes of changing width, least significant bit first
+    struct BitReader<'a> {
+        bytes: std::slice::Iter<'a, u8>,
+        acc: u32,
+        len: u8,
+    }
+
+    impl BitReader<'_> {
+        fn read(&mut self, width: u8) -> u16 {
+            while self.len < width {
+                self.acc |= (*self.bytes.next().expect("stream ended before end code") as u32) << self.len;
+                self.len += 8;
+            }
+            let code = self.acc & ((1 << width) - 1);
+            self.acc >>= width;
+            self.len -= width;
+            code as u16
+        }
+    }
+
+    /// Plain GIF LZW decoder, independent of the encoder's dictionary
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+
+        let mut input = BitReader { bytes: data.iter(), acc: 0, len: 0 };
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut width = min_code_size + 1;
+        let mut prev: Option<usize> = None;
+        let mut out = Vec::new();
+
+        loop {
+            let code = input.read(width) as usize;
+            if code == clear {
+                // clear and end codes get placeholder entries
+                table = (0..clear).map(|i| vec![i as u8]).chain([vec![], vec![]]).collect();
+                width = min_code_size + 1;
+                prev = None;
+                continue
+            }
+            if code == end {
+                return out
+            }
+
+            let entry = match prev {
+                Some(prev) if code == table.len() => {
+                    let mut entry = table[prev].clone();
+                    entry.push(entry[0]);
+                    entry
+                },
+                _ => table[code].clone(),
+            };
+
+            if let Some(prev) = prev {
+                let mut added = table[prev].clone();
+                added.push(entry[0]);
+                table.push(added);
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+
+            out.extend(&entry);
+            prev = Some(code);
+        }
+    }
+
+    /// Deterministic noise in `0..range`
+    fn noise(len: usize, range: u8) -> Vec<u8> {
+        let mut state: u32 = 0x2545f491;
+        (0..len).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state % range as u32) as u8
+        }).collect()
+    }
+
+    #[test]
+    fn lzw_known_codes() {
+        // clear, 0, (0,0), 0, end at 3 bits each
+        assert_eq!(lzw_encode(&[0, 0, 0, 0], 2), [0x84, 0x51]);
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let inputs = [
+            (vec![], 2),
+            (vec![3], 2),
+            (vec![1; 10_000], 2),
+            (noise(20_000, 4), 2),
+            (noise(20_000, 16), 4),
+            (noise(50_000, 255), 8),
+        ];
+
+        for (indices, min_code_size) in inputs {
+            assert_eq!(lzw_decode(&lzw_encode(&indices, min_code_size), min_code_size), indices);
+        }
+    }
+
+    #[test]
+    fn wants_counts_from_range_start() {
+        let recorder: FrameRecorder<bool> = FrameRecorder::new().steps(5..40).every(3);
+        let wanted: Vec<usize> = (0..50).filter(|&step| recorder.wants(step)).collect();
+        assert_eq!(wanted, [5, 8, 11, 14, 17, 20, 23, 26, 29, 32, 35, 38]);
+
+        let recorder: FrameRecorder<bool> = FrameRecorder::new().every(4);
+        assert!(recorder.wants(0) && recorder.wants(8) && !recorder.wants(6));
+    }
+
+    #[test]
+    fn gif_round_trip() {
+        let palette = |&cell: &u8| [Rgb::BLACK, Rgb::WHITE, Rgb(205, 49, 49)][cell as usize];
+        let render = |grid: &Grid<u8>| ImageExporter::new(grid, palette).cell_size(2).render();
+
+        let mut recorder = FrameRecorder::new();
+        for step in 0..4 {
+            recorder.record_with(step, || Grid::from_fn(5, 3, |(col, row)| ((col + row + step) % 3) as u8));
+        }
+
+        let mut gif = Vec::new();
+        recorder.write_gif(&mut gif, 7, render).unwrap();
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif[6..10], [10, 0, 6, 0]);
+        // 3 colors need a table of 4
+        let table_size = 2 << (gif[10] & 7);
+        assert_eq!(table_size, 4);
+        let table: Vec<Rgb> = gif[13..13 + 3 * table_size].chunks(3).map(|rgb| Rgb(rgb[0], rgb[1], rgb[2])).collect();
+
+        let mut i = 13 + 3 * table_size;
+        let mut decoded = Vec::new();
+        loop {
+            match gif[i] {
+                // extension, skip its sub-blocks
+                0x21 => {
+                    if gif[i + 1] == 0xf9 {
+                        assert_eq!(gif[i + 4..i + 6], 7u16.to_le_bytes());
+                    }
+                    i += 2;
+                    while gif[i] != 0 {
+                        i += gif[i] as usize + 1;
+                    }
+                    i += 1;
+                },
+                0x2c => {
+                    let min_code_size = gif[i + 10];
+                    i += 11;
+                    let mut data = Vec::new();
+                    while gif[i] != 0 {
+                        data.extend(&gif[i + 1..=i + gif[i] as usize]);
+                        i += gif[i] as usize + 1;
+                    }
+                    i += 1;
+
+                    let pixels: Vec<Rgb> = lzw_decode(&data, min_code_size).into_iter().map(|index| table[index as usize]).collect();
+                    decoded.push(pixels);
+                },
+                0x3b => break,
+                other => panic!("unexpected block {other:#x}"),
+            }
+        }
+
+        let expected: Vec<Vec<Rgb>> = recorder.frames().iter().map(|(_, grid)| render(grid).pixels().to_vec()).collect();
+        assert_eq!(decoded, expected);
+    }
+}
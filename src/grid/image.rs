@@ -0,0 +1,328 @@
+//! Image export of grids.
+//!
+//! Images are written without any dependencies, either as binary PPM
+//! or as PNG with uncompressed (stored) deflate blocks.
+
+use std::{collections::HashSet, fs::File, io::{self, BufWriter, Write}, path::Path};
+
+use super::{render::Color, Grid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+}
+
+// map terminal colors to something that looks about the same
+impl From<Color> for Rgb {
+    fn from(value: Color) -> Self {
+        match value {
+            Color::Black => Rgb(0, 0, 0),
+            Color::Red => Rgb(205, 49, 49),
+            Color::Green => Rgb(13, 188, 121),
+            Color::Yellow => Rgb(229, 229, 16),
+            Color::Blue => Rgb(36, 114, 200),
+            Color::Magenta => Rgb(188, 63, 188),
+            Color::Cyan => Rgb(17, 168, 205),
+            Color::White => Rgb(229, 229, 229),
+        }
+    }
+}
+
+/// Plain RGB image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pixels: Vec<Rgb>,
+    width: usize,
+    height: usize,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self { pixels: vec![fill; width * height], width, height }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None
+        }
+        Some(self.pixels[x + y * self.width])
+    }
+
+    /// Pixels in row-major order
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    /// Fill a `size`x`size` square whose top-left corner is at `size*col, size*row`
+    fn fill_cell(&mut self, (col, row): (usize, usize), size: usize, color: Rgb) {
+        for y in row * size..(row + 1) * size {
+            let start = col * size + y * self.width;
+            self.pixels[start..start + size].fill(color);
+        }
+    }
+
+    /// Write image as binary (P6) PPM
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for &Rgb(r, g, b) in &self.pixels {
+            out.write_all(&[r, g, b])?;
+        }
+        out.flush()
+    }
+
+    /// Write image as PNG
+    pub fn write_png(&self, mut out: impl Write) -> io::Result<()> {
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, truecolor, default compression, filter and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+        write_png_chunk(&mut out, b"IHDR", &header)?;
+
+        // every scanline starts with its filter type, 0 being none
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for line in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            for &Rgb(r, g, b) in line {
+                raw.extend([r, g, b]);
+            }
+        }
+        write_png_chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+        write_png_chunk(&mut out, b"IEND", &[])?;
+
+        out.flush()
+    }
+
+    /// Save image to a file, format is picked by extension (`.png`, otherwise PPM)
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let out = BufWriter::new(File::create(path)?);
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("png") => self.write_png(out),
+            _ => self.write_ppm(out),
+        }
+    }
+}
+
+fn write_png_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// Wrap data into a zlib stream without actually compressing it
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    // CMF/FLG pair for 32K window, no dictionary, fastest compression
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        // empty input still needs one final block
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb88320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (mut a, mut b) = (1, 0);
+    for &byte in bytes {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+/// Draws a grid and its overlays into an [`Image`].
+///
+/// Works like [`Renderer`](super::render::Renderer), but every cell
+/// becomes a square of pixels colored by the palette.
+pub struct ImageExporter<'a, T> {
+    grid: &'a Grid<T>,
+    palette: Box<dyn Fn(&T) -> Rgb + 'a>,
+    overlays: Vec<(HashSet<(usize, usize)>, Rgb)>,
+    cell_size: usize,
+}
+
+impl<'a, T> ImageExporter<'a, T> {
+    pub fn new(grid: &'a Grid<T>, palette: impl Fn(&T) -> Rgb + 'a) -> Self {
+        Self {
+            grid,
+            palette: Box::new(palette),
+            overlays: Vec::new(),
+            cell_size: 1,
+        }
+    }
+
+    /// Size of one grid cell in pixels
+    pub fn cell_size(mut self, size: usize) -> Self {
+        self.cell_size = size.max(1);
+        self
+    }
+
+    /// Paint cells with a color, drawn on top of previous overlays
+    pub fn overlay(mut self, cells: impl IntoIterator<Item = (usize, usize)>, color: impl Into<Rgb>) -> Self {
+        self.overlays.push((cells.into_iter().collect(), color.into()));
+        self
+    }
+
+    pub fn render(&self) -> Image {
+        let size = self.cell_size;
+        let mut image = Image::new(self.grid.width() * size, self.grid.height() * size, Rgb::BLACK);
+
+        for (pos, tile) in self.grid.iter_indexed() {
+            let color = self.overlays.iter().rev()
+                .find(|(cells, _)| cells.contains(&pos))
+                .map_or_else(|| (self.palette)(tile), |&(_, color)| color);
+
+            image.fill_cell(pos, size, color);
+        }
+
+        image
+    }
+
+    /// Shorthand for rendering and saving the image
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.render().save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unwrap a zlib stream made of stored blocks only
+    fn unzlib_stored(stream: &[u8]) -> Vec<u8> {
+        assert_eq!(u16::from_be_bytes([stream[0], stream[1]]) % 31, 0, "bad zlib header check");
+
+        let mut data = Vec::new();
+        let mut i = 2;
+        loop {
+            let header = stream[i];
+            assert_eq!(header & 0b110, 0, "only stored blocks expected");
+            let len = u16::from_le_bytes([stream[i + 1], stream[i + 2]]);
+            let nlen = u16::from_le_bytes([stream[i + 3], stream[i + 4]]);
+            assert_eq!(len, !nlen);
+
+            i += 5;
+            data.extend(&stream[i..i + len as usize]);
+            i += len as usize;
+
+            if header & 1 == 1 {
+                break
+            }
+        }
+
+        assert_eq!(stream[i..], adler32(&data).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn zlib_stored_round_trip() {
+        for len in [0, 1, 65_535, 65_536, 200_000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 7 % 251) as u8).collect();
+            assert_eq!(unzlib_stored(&zlib_stored(&data)), data);
+        }
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let grid = Grid::from_fn(3, 2, |(col, row)| col + 3 * row);
+        let palette = |&i: &usize| Rgb(i as u8 * 40, 255 - i as u8, 7);
+        let image = ImageExporter::new(&grid, palette).cell_size(2).render();
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut chunks = Vec::new();
+        let mut i = 8;
+        while i < png.len() {
+            let len = u32::from_be_bytes(png[i..i + 4].try_into().unwrap()) as usize;
+            let (kind, data) = (&png[i + 4..i + 8], &png[i + 8..i + 8 + len]);
+            let crc = u32::from_be_bytes(png[i + 8 + len..i + 12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32(kind.iter().chain(data)));
+
+            chunks.push((kind, data));
+            i += 12 + len;
+        }
+
+        let kinds: Vec<&[u8]> = chunks.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 6, 0, 0, 0, 4, 8, 2, 0, 0, 0]);
+
+        // every scanline is unfiltered, so pixels follow its filter byte
+        let raw = unzlib_stored(chunks[1].1);
+        let pixels: Vec<Rgb> = raw.chunks(1 + 3 * 6)
+            .flat_map(|line| {
+                assert_eq!(line[0], 0);
+                line[1..].chunks(3).map(|rgb| Rgb(rgb[0], rgb[1], rgb[2]))
+            })
+            .collect();
+        assert_eq!(pixels, image.pixels());
+    }
+
+    #[test]
+    fn exporter_overlays() {
+        let grid = Grid::from_fn(2, 2, |_| ());
+        let image = ImageExporter::new(&grid, |_| Rgb::WHITE)
+            .overlay([(0, 0), (1, 1)], Rgb(1, 2, 3))
+            .overlay([(1, 1)], Color::Red)
+            .render();
+
+        assert_eq!(image.pixels(), [Rgb(1, 2, 3), Rgb::WHITE, Rgb::WHITE, Rgb::from(Color::Red)]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Undoable grid mutations.
+
+use super::{Grid, GridView};
+
+/// Position in a [`Journal`] to roll back to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Checkpoint(usize);
+
+/// Mutable grid wrapper which remembers old values of every written cell.
+///
+/// Changes go straight into the grid, rolling back restores old values in reverse order.
+#[derive(Debug)]
+pub struct Journal<'a, T> {
+    grid: &'a mut Grid<T>,
+    /// Old values in order of writes
+    log: Vec<((usize, usize), T)>,
+    /// Log length when current step started
+    step_start: usize,
+}
+
+impl<'a, T: Clone> Journal<'a, T> {
+    pub fn new(grid: &'a mut Grid<T>) -> Self {
+        Self { grid, log: Vec::new(), step_start: 0 }
+    }
+
+    /// Mutable reference to a cell, its old value is recorded before handing it out
+    pub fn at_mut(&mut self, col: usize, row: usize) -> Option<&mut T> {
+        self.record_offset((col, row), 0, 0).map(|thing| thing.0)
+    }
+
+    /// Record old value of cell at offset and return it for writing
+    fn record_offset(&mut self, (col, row): (usize, usize), col_offset: isize, row_offset: isize) -> Option<(&mut T, usize, usize)> {
+        let (thing, col, row) = self.grid
+            .entry_mut(col, row)
+            .into_offset_mut(col_offset, row_offset)?;
+
+        self.log.push(((col, row), thing.clone()));
+        Some((thing, col, row))
+    }
+
+    /// Overwrite a cell, returns `false` if it's out of bounds
+    pub fn set(&mut self, col: usize, row: usize, value: T) -> bool {
+        self.at_mut(col, row).map(|thing| *thing = value).is_some()
+    }
+
+    /// Swap contents of two cells, returns `false` if either is out of bounds
+    pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) -> bool {
+        let (Some(first), Some(second)) = (self.get(a.0, a.1).cloned(), self.get(b.0, b.1).cloned()) else {
+            return false
+        };
+        self.set(a.0, a.1, second);
+        self.set(b.0, b.1, first);
+        true
+    }
+
+    pub fn entry_mut(&mut self, col: usize, row: usize) -> JournalEntryMut<'_, 'a, T> {
+        JournalEntryMut { journal: self, col, row }
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.log.len())
+    }
+
+    /// Undo every write made after `checkpoint`
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        while self.log.len() > checkpoint.0 {
+            let ((col, row), old) = self.log.pop().unwrap();
+            *self.grid.at_mut(col, row).unwrap() = old;
+        }
+        self.step_start = self.step_start.min(checkpoint.0);
+    }
+
+    /// Undo every write since journal was created or last committed
+    pub fn rollback_all(&mut self) {
+        self.rollback(Checkpoint(0));
+    }
+
+    /// Keep all changes and forget their history
+    pub fn commit(&mut self) {
+        self.log.clear();
+        self.step_start = 0;
+    }
+
+    /// Cells written after `checkpoint` in order of first write.
+    ///
+    /// Cells which were written back to their old value are included too.
+    pub fn changed_since(&self, checkpoint: Checkpoint) -> Vec<(usize, usize)> {
+        let mut changed: Vec<(usize, usize)> = Vec::new();
+        for &(pos, _) in self.log.iter().skip(checkpoint.0) {
+            if !changed.contains(&pos) {
+                changed.push(pos);
+            }
+        }
+        changed
+    }
+
+    /// Finish current step and return cells written during it
+    pub fn end_step(&mut self) -> Vec<(usize, usize)> {
+        let changed = self.changed_since(Checkpoint(self.step_start));
+        self.step_start = self.log.len();
+        changed
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        self.grid
+    }
+}
+
+impl<T> GridView<T> for Journal<'_, T> {
+    fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    fn get(&self, col: usize, row: usize) -> Option<&T> {
+        self.grid.get(col, row)
+    }
+}
+
+/// Like [`GridEntryMut`](super::GridEntryMut), but writes are recorded in a [`Journal`]
+pub struct JournalEntryMut<'j, 'a, T> {
+    journal: &'j mut Journal<'a, T>,
+    col: usize,
+    row: usize,
+}
+
+impl<T: Clone> JournalEntryMut<'_, '_, T> {
+    pub fn at_offset_mut(&mut self, col_offset: isize, row_offset: isize) -> Option<&mut T> {
+        self.offset_mut(col_offset, row_offset).map(|thing| thing.0)
+    }
+
+    /// Returns item at offset and its true column and row index if valid
+    pub fn offset_mut(&mut self, col_offset: isize, row_offset: isize) -> Option<(&mut T, usize, usize)> {
+        self.journal.record_offset((self.col, self.row), col_offset, row_offset)
+    }
+
+    /// Overwrite item at offset, returns `false` if it's out of bounds
+    pub fn set_offset(&mut self, col_offset: isize, row_offset: isize, value: T) -> bool {
+        self.at_offset_mut(col_offset, row_offset).map(|thing| *thing = value).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef").unwrap()
+    }
+
+    #[test]
+    fn rollback_to_checkpoints() {
+        let mut grid = grid();
+        let mut journal = Journal::new(&mut grid);
+
+        assert!(journal.set(0, 0, 'x'));
+        let first = journal.checkpoint();
+        assert!(journal.set(1, 0, 'y'));
+        assert!(journal.set(0, 0, 'z'));
+        let second = journal.checkpoint();
+        assert!(journal.swap((2, 0), (2, 1)));
+        assert_eq!(*journal.grid(), Grid::parse("zyf\ndec").unwrap());
+
+        journal.rollback(second);
+        assert_eq!(*journal.grid(), Grid::parse("zyc\ndef").unwrap());
+
+        // rolling back to an earlier checkpoint restores the oldest value of every cell
+        journal.rollback(first);
+        assert_eq!(*journal.grid(), Grid::parse("xbc\ndef").unwrap());
+
+        journal.rollback_all();
+        assert_eq!(*journal.grid(), self::grid());
+    }
+
+    #[test]
+    fn out_of_bounds_writes() {
+        let mut grid = grid();
+        let mut journal = Journal::new(&mut grid);
+
+        assert!(!journal.set(3, 0, 'x'));
+        assert!(!journal.set(0, 2, 'x'));
+        assert!(!journal.swap((0, 0), (5, 5)));
+        assert!(journal.changed_since(Checkpoint(0)).is_empty());
+        assert_eq!(*journal.grid(), self::grid());
+    }
+
+    #[test]
+    fn commit_keeps_changes() {
+        let mut grid = grid();
+        let mut journal = Journal::new(&mut grid);
+
+        journal.set(1, 1, 'x');
+        journal.commit();
+        journal.set(2, 1, 'y');
+        journal.rollback_all();
+
+        assert_eq!(*journal.grid(), Grid::parse("abc\ndxf").unwrap());
+    }
+
+    #[test]
+    fn changed_cells_per_step() {
+        let mut grid = grid();
+        let mut journal = Journal::new(&mut grid);
+
+        journal.set(2, 0, 'x');
+        journal.set(0, 1, 'y');
+        journal.set(2, 0, 'c');
+        assert_eq!(journal.end_step(), [(2, 0), (0, 1)]);
+
+        let mut entry = journal.entry_mut(1, 1);
+        assert!(entry.set_offset(0, -1, 'z'));
+        assert!(!entry.set_offset(0, 1, 'z'));
+        *entry.at_offset_mut(1, 0).unwrap() = 'w';
+        assert_eq!(journal.end_step(), [(1, 0), (2, 1)]);
+        assert!(journal.end_step().is_empty());
+
+        // undo second step and a pending write, steps continue from there
+        journal.set(0, 0, 'v');
+        journal.rollback(Checkpoint(3));
+        assert_eq!(*journal.grid(), Grid::parse("abc\nyef").unwrap());
+
+        journal.set(1, 0, 'u');
+        assert_eq!(journal.end_step(), [(1, 0)]);
+    }
+}
@@ -0,0 +1,38 @@
+//! Cells within a distance of a point, clipped to the grid.
+
+use super::{GridEntry, GridView};
+
+impl<T, G: GridView<T> + ?Sized> GridEntry<'_, T, G> {
+    /// Positions within Manhattan distance `radius` and their distance, including entry itself.
+    ///
+    /// Only rows and columns inside the grid are visited.
+    pub fn within_manhattan(&self, radius: usize) -> impl Iterator<Item = ((usize, usize), usize)> {
+        let (col, row) = (self.col, self.row);
+        let (width, height) = (self.grid.width(), self.grid.height());
+
+        clipped(row, radius, height).flat_map(move |r| {
+            let row_dist = r.abs_diff(row);
+            // diamond gets narrower the further we are from center row
+            clipped(col, radius - row_dist, width).map(move |c| ((c, r), row_dist + c.abs_diff(col)))
+        })
+    }
+
+    /// Positions within Chebyshev distance `radius` and their distance, including entry itself.
+    ///
+    /// Only rows and columns inside the grid are visited.
+    pub fn within_chebyshev(&self, radius: usize) -> impl Iterator<Item = ((usize, usize), usize)> {
+        let (col, row) = (self.col, self.row);
+        let (width, height) = (self.grid.width(), self.grid.height());
+
+        clipped(row, radius, height).flat_map(move |r| {
+            clipped(col, radius, width).map(move |c| ((c, r), r.abs_diff(row).max(c.abs_diff(col))))
+        })
+    }
+}
+
+/// Indices within `radius` of `center` which are below `len`
+fn clipped(center: usize, radius: usize, len: usize) -> std::ops::Range<usize> {
+    let start = center.saturating_sub(radius);
+    let end = center.saturating_add(radius).saturating_add(1).min(len);
+    start..end.max(start)
+}
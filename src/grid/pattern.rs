@@ -0,0 +1,127 @@
+//! Searching grids for words and 2D templates.
+
+use super::{transform::Transform, Grid, GridView, ParseGridError};
+
+/// All eight directions, starting right and going clockwise
+pub const EIGHT_WAY: [(isize, isize); 8] = [
+    (1, 0), (1, 1), (0, 1), (-1, 1),
+    (-1, 0), (-1, -1), (0, -1), (1, -1),
+];
+
+/// Occurrence of a word, see [`Grid::find_word`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    /// Position of the first item of the word
+    pub start: (usize, usize),
+    /// Direction in which the word continues
+    pub dir: (isize, isize),
+}
+
+/// Occurrence of a template, see [`Grid::find_template`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TemplateMatch {
+    /// Position of the top-left corner of oriented template
+    pub pos: (usize, usize),
+    /// How the template was rotated or reflected to match
+    pub transform: Transform,
+}
+
+/// 2D pattern where `None` cells match anything.
+///
+/// Holds each distinct orientation of the pattern, so symmetric patterns match only once.
+#[derive(Debug, Clone)]
+pub struct Template<T> {
+    orientations: Vec<(Transform, Grid<Option<T>>)>,
+}
+
+impl<T: Clone + PartialEq> Template<T> {
+    /// Template that only matches in its original orientation
+    pub fn new(cells: Grid<Option<T>>) -> Self {
+        Self { orientations: vec![(Transform::Identity, cells)] }
+    }
+
+    /// Template that matches under all rotations and reflections
+    pub fn any_orientation(cells: Grid<Option<T>>) -> Self {
+        Self::with_transforms(cells, &Transform::ALL)
+    }
+
+    /// Template that matches under given transforms
+    pub fn with_transforms(cells: Grid<Option<T>>, transforms: &[Transform]) -> Self {
+        let mut orientations: Vec<(Transform, Grid<Option<T>>)> = Vec::new();
+        for &transform in transforms {
+            let oriented = cells.transformed(transform).to_grid();
+            if !orientations.iter().any(|(_, seen)| *seen == oriented) {
+                orientations.push((transform, oriented));
+            }
+        }
+
+        Self { orientations }
+    }
+
+    /// Transforms under which this template is searched, one per distinct orientation
+    pub fn transforms(&self) -> impl Iterator<Item = Transform> + '_ {
+        self.orientations.iter().map(|(transform, _)| *transform)
+    }
+}
+
+impl Template<char> {
+    /// Parse template cells from text, `wildcard` matches any character
+    pub fn parse_cells(pattern: &str, wildcard: char) -> Result<Grid<Option<char>>, ParseGridError> {
+        Grid::parse_lines(pattern, |chr, _, _| Ok((chr != wildcard).then_some(chr)))
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Find every occurrence of `word` in any of the 8 directions.
+    ///
+    /// Single item words are reported only once per position.
+    pub fn find_word(&self, word: &[T]) -> Vec<WordMatch> {
+        let Some((first, rest)) = word.split_first() else {
+            return Vec::new()
+        };
+        let dirs = if rest.is_empty() { &EIGHT_WAY[..1] } else { &EIGHT_WAY[..] };
+
+        let mut matches = Vec::new();
+        for (start, thing) in self.iter_indexed() {
+            if thing != first {
+                continue
+            }
+
+            let entry = self.entry(start.0, start.1);
+            for &dir in dirs {
+                let mut ray = entry.ray(dir);
+                if rest.iter().all(|expected| ray.next().is_some_and(|(thing, _, _)| thing == expected)) {
+                    matches.push(WordMatch { start, dir });
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Find every position where `template` matches in any of its orientations
+    pub fn find_template(&self, template: &Template<T>) -> Vec<TemplateMatch> {
+        let mut matches = Vec::new();
+
+        for (transform, cells) in &template.orientations {
+            if cells.width() > self.width || cells.height() > self.height {
+                continue
+            }
+
+            for row in 0..=self.height - cells.height() {
+                for col in 0..=self.width - cells.width() {
+                    let fits = cells.iter_indexed().all(|((dc, dr), expected)| match expected {
+                        Some(expected) => self.get(col + dc, row + dr) == Some(expected),
+                        None => true,
+                    });
+
+                    if fits {
+                        matches.push(TemplateMatch { pos: (col, row), transform: *transform });
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+}
@@ -0,0 +1,185 @@
+//! Walking grids in a straight line.
+
+use std::marker::PhantomData;
+
+use super::{Grid, GridEntry, GridView};
+
+impl<'a, T, G: GridView<T> + ?Sized> GridEntry<'a, T, G> {
+    /// Iterate cells along `step` until grid edge, entry's own cell is not included.
+    ///
+    /// Panics if `step` is (0, 0).
+    pub fn ray(&self, step: (isize, isize)) -> Ray<'a, T, G> {
+        assert!(step != (0, 0), "ray step can't be zero");
+        Ray { grid: self.grid, pos: (self.col, self.row), step, tile: PhantomData }
+    }
+}
+
+/// Iterator over cells in a straight line, see [`GridEntry::ray`]
+#[derive(Debug)]
+pub struct Ray<'a, T, G: ?Sized = Grid<T>> {
+    grid: &'a G,
+    pos: (usize, usize),
+    step: (isize, isize),
+    tile: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, G: GridView<T> + ?Sized> Ray<'a, T, G> {
+    /// Position of last yielded cell, entry's position before first step
+    pub fn pos(&self) -> (usize, usize) {
+        self.pos
+    }
+
+    /// Next cell without moving the ray
+    pub fn peek(&self) -> Option<(&'a T, usize, usize)> {
+        let col = self.pos.0.checked_add_signed(self.step.0)?;
+        let row = self.pos.1.checked_add_signed(self.step.1)?;
+
+        if col >= self.grid.width() { return None }
+        if row >= self.grid.height() { return None }
+
+        self.grid.get(col, row).map(|thing| (thing, col, row))
+    }
+
+    /// Yield cells until `blocked` returns true, blocking cell is kept in [`TakeUntil::blocked`]
+    pub fn take_until<P: FnMut(&T) -> bool>(self, blocked: P) -> TakeUntil<'a, T, G, P> {
+        TakeUntil { ray: self, pred: blocked, blocked: None, done: false }
+    }
+}
+
+impl<'a, T: 'a, G: GridView<T> + ?Sized> Iterator for Ray<'a, T, G> {
+    type Item = (&'a T, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek()?;
+        self.pos = (item.1, item.2);
+        Some(item)
+    }
+}
+
+/// Where a [`TakeUntil`] ray stopped
+#[derive(Debug, Clone, Copy)]
+pub struct RayStop<'a, T> {
+    /// Last free cell, ray's starting position if first cell was blocked
+    pub last_free: (usize, usize),
+    /// Blocking cell, `None` if ray left the grid
+    pub blocked: Option<(&'a T, usize, usize)>,
+}
+
+/// Ray that stops in front of a blocking cell, see [`Ray::take_until`]
+#[derive(Debug)]
+pub struct TakeUntil<'a, T, G: ?Sized, P> {
+    ray: Ray<'a, T, G>,
+    pred: P,
+    blocked: Option<(&'a T, usize, usize)>,
+    done: bool,
+}
+
+impl<'a, T: 'a, G: GridView<T> + ?Sized, P: FnMut(&T) -> bool> TakeUntil<'a, T, G, P> {
+    /// Last free cell walked so far, entry's position if none
+    pub fn last_free(&self) -> (usize, usize) {
+        self.ray.pos()
+    }
+
+    /// Cell that stopped the ray, `None` if not reached yet or ray left the grid
+    pub fn blocked(&self) -> Option<(&'a T, usize, usize)> {
+        self.blocked
+    }
+
+    /// Walk to the end and return where the ray stopped
+    pub fn finish(mut self) -> RayStop<'a, T> {
+        self.by_ref().for_each(drop);
+        RayStop { last_free: self.last_free(), blocked: self.blocked }
+    }
+}
+
+impl<'a, T: 'a, G: GridView<T> + ?Sized, P: FnMut(&T) -> bool> Iterator for TakeUntil<'a, T, G, P> {
+    type Item = (&'a T, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+
+        // blocking cell must not move the ray
+        match self.ray.peek() {
+            Some((thing, col, row)) if (self.pred)(thing) => {
+                self.blocked = Some((thing, col, row));
+                self.done = true;
+                None
+            },
+            Some(_) => self.ray.next(),
+            None => {
+                self.done = true;
+                None
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::transform::Transform;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abcd\nefgh\nijkl").unwrap()
+    }
+
+    fn chars<'a>(ray: impl Iterator<Item = (&'a char, usize, usize)>) -> String {
+        ray.map(|(&chr, _, _)| chr).collect()
+    }
+
+    #[test]
+    fn rays_stop_at_edges() {
+        let grid = grid();
+        let entry = grid.entry(1, 1);
+
+        assert_eq!(chars(entry.ray((1, 0))), "gh");
+        assert_eq!(chars(entry.ray((-1, 0))), "e");
+        assert_eq!(chars(entry.ray((0, -1))), "b");
+        assert_eq!(chars(entry.ray((1, 1))), "k");
+        assert_eq!(chars(entry.ray((-1, -1))), "a");
+        assert_eq!(chars(grid.entry(0, 0).ray((2, 1))), "g");
+
+        let mut ray = entry.ray((1, 0));
+        assert_eq!(ray.peek(), Some((&'g', 2, 1)));
+        assert_eq!(ray.pos(), (1, 1));
+        ray.next();
+        assert_eq!(ray.pos(), (2, 1));
+    }
+
+    #[test]
+    fn rays_on_views() {
+        let grid = grid();
+        let rotated = grid.transformed(Transform::RotateCw);
+        assert_eq!(chars(rotated.entry(0, 0).ray((1, 0))), "ea");
+        assert_eq!(chars(rotated.entry(2, 3).ray((0, -1))), "cba");
+    }
+
+    #[test]
+    fn take_until_blocked() {
+        let grid = grid();
+
+        let mut walk = grid.entry(0, 0).ray((1, 0)).take_until(|&chr| chr == 'd');
+        assert_eq!(chars(walk.by_ref()), "bc");
+        assert_eq!(walk.last_free(), (2, 0));
+        assert_eq!(walk.blocked(), Some((&'d', 3, 0)));
+        assert_eq!(walk.next(), None);
+
+        // blocked right away
+        let stop = grid.entry(0, 0).ray((0, 1)).take_until(|&chr| chr == 'e').finish();
+        assert_eq!(stop.last_free, (0, 0));
+        assert_eq!(stop.blocked, Some((&'e', 0, 1)));
+
+        // never blocked
+        let stop = grid.entry(0, 0).ray((0, 1)).take_until(|_| false).finish();
+        assert_eq!(stop.last_free, (0, 2));
+        assert_eq!(stop.blocked, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ray step can't be zero")]
+    fn zero_step() {
+        grid().entry(0, 0).ray((0, 0));
+    }
+}
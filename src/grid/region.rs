@@ -0,0 +1,338 @@
+//! Flood fill and connected components.
+
+use super::{bits::BitGrid, Grid};
+
+/// Offsets of 4-connected neighbors
+pub(super) const ORTHOGONAL: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// One connected region of a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// Cells in discovery order
+    pub cells: Vec<(usize, usize)>,
+    /// Top-left corner of bounding box
+    pub min: (usize, usize),
+    /// Bottom-right corner of bounding box, inclusive
+    pub max: (usize, usize),
+}
+
+impl Component {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Width and height of bounding box
+    pub fn size(&self) -> (usize, usize) {
+        (self.max.0 - self.min.0 + 1, self.max.1 - self.min.1 + 1)
+    }
+}
+
+/// Result of connected component labeling
+#[derive(Debug, Clone)]
+pub struct Components {
+    /// Index of the component each cell belongs to
+    pub labels: Grid<usize>,
+    pub regions: Vec<Component>,
+}
+
+impl Components {
+    /// Component containing given cell
+    pub fn at(&self, col: usize, row: usize) -> Option<&Component> {
+        if col >= self.labels.width {
+            return None
+        }
+        let label = *self.labels.content.get(col + row * self.labels.width)?;
+        self.regions.get(label)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Position of a 4-connected neighbor if it's within grid
+    pub(super) fn neighbor(&self, (col, row): (usize, usize), (dc, dr): (isize, isize)) -> Option<(usize, usize)> {
+        let col = col.checked_add_signed(dc).filter(|&col| col < self.width)?;
+        let row = row.checked_add_signed(dr).filter(|&row| row < self.height)?;
+        Some((col, row))
+    }
+
+    /// Depth-first fill from `start` over 4-connected cells for which `connected(from, to)` holds
+    fn fill(
+        &self,
+        start: (usize, usize),
+        visited: &mut BitGrid,
+        connected: &impl Fn(&T, &T) -> bool,
+    ) -> Component {
+        let mut stack = vec![start];
+        visited.set(start.0, start.1);
+
+        let mut component = Component { cells: Vec::new(), min: start, max: start };
+
+        while let Some(pos) = stack.pop() {
+            component.cells.push(pos);
+            component.min = (component.min.0.min(pos.0), component.min.1.min(pos.1));
+            component.max = (component.max.0.max(pos.0), component.max.1.max(pos.1));
+
+            let here = &self.content[pos.0 + pos.1 * self.width];
+            for dir in ORTHOGONAL {
+                let Some(next) = self.neighbor(pos, dir) else { continue };
+                if !visited.test(next.0, next.1) && connected(here, &self.content[next.0 + next.1 * self.width]) {
+                    visited.set(next.0, next.1);
+                    stack.push(next);
+                }
+            }
+        }
+
+        component
+    }
+
+    /// Find all cells 4-connected to `start` which satisfy the predicate.
+    ///
+    /// Returns an empty list if start itself doesn't satisfy it.
+    pub fn flood_fill(&self, start: (usize, usize), pred: impl Fn(&T) -> bool) -> Vec<(usize, usize)> {
+        if start.0 >= self.width || start.1 >= self.height {
+            return Vec::new()
+        }
+        if !pred(&self.content[start.0 + start.1 * self.width]) {
+            return Vec::new()
+        }
+
+        let mut visited = BitGrid::new(self.width, self.height);
+        self.fill(start, &mut visited, &|_, to| pred(to)).cells
+    }
+
+    /// Label 4-connected components, neighbors belong to the same one if `same_region` holds
+    pub fn components(&self, same_region: impl Fn(&T, &T) -> bool) -> Components {
+        let mut visited = BitGrid::new(self.width, self.height);
+        let mut labels = vec![0; self.content.len()];
+        let mut regions = Vec::new();
+
+        for i in 0..self.content.len() {
+            let start = (i % self.width, i / self.width);
+            if visited.test(start.0, start.1) {
+                continue
+            }
+
+            let component = self.fill(start, &mut visited, &same_region);
+            for &(col, row) in &component.cells {
+                labels[col + row * self.width] = regions.len();
+            }
+            regions.push(component);
+        }
+
+        Components {
+            labels: Grid { content: labels, width: self.width, height: self.height },
+            regions,
+        }
+    }
+}
+
+/// Corners of a region's outline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Corners {
+    /// Corners pointing out of the region
+    pub convex: usize,
+    /// Corners pointing into the region
+    pub concave: usize,
+}
+
+impl Corners {
+    pub fn total(&self) -> usize {
+        self.convex + self.concave
+    }
+}
+
+impl Components {
+    /// Check if signed position is inside grid and belongs to a component
+    fn is_label(&self, label: usize, (col, row): (isize, isize)) -> bool {
+        if col < 0 || row < 0 || col as usize >= self.labels.width || row as usize >= self.labels.height {
+            return false
+        }
+        self.labels.content[col as usize + row as usize * self.labels.width] == label
+    }
+
+    /// Perimeter edges of a component as (cell, outward direction) pairs.
+    ///
+    /// Grid border counts as an edge too.
+    pub fn fences(&self, label: usize) -> impl Iterator<Item = ((usize, usize), (isize, isize))> + '_ {
+        self.regions[label].cells.iter().flat_map(move |&(col, row)| {
+            ORTHOGONAL.into_iter()
+                .filter(move |&(dc, dr)| !self.is_label(label, (col as isize + dc, row as isize + dr)))
+                .map(move |dir| ((col, row), dir))
+        })
+    }
+
+    pub fn perimeter(&self, label: usize) -> usize {
+        self.fences(label).count()
+    }
+
+    /// Number of straight sides, neighboring fences facing the same way are merged into one.
+    pub fn sides(&self, label: usize) -> usize {
+        self.fences(label)
+            .filter(|&((col, row), (dc, dr))| {
+                // previous cell along the fence, rotated direction
+                let (pc, pr) = (col as isize + dr, row as isize - dc);
+
+                // side continues from previous cell if it has the same fence
+                let continues = self.is_label(label, (pc, pr))
+                    && !self.is_label(label, (pc + dc, pr + dr));
+
+                !continues
+            })
+            .count()
+    }
+
+    /// Count convex and concave corners of a component.
+    ///
+    /// Outline of a region without holes has as many corners as it has sides.
+    pub fn corners(&self, label: usize) -> Corners {
+        let mut corners = Corners::default();
+
+        for &(col, row) in &self.regions[label].cells {
+            let (col, row) = (col as isize, row as isize);
+
+            for (dc, dr) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let horizontal = self.is_label(label, (col + dc, row));
+                let vertical = self.is_label(label, (col, row + dr));
+                let diagonal = self.is_label(label, (col + dc, row + dr));
+
+                if !horizontal && !vertical {
+                    corners.convex += 1;
+                } else if horizontal && vertical && !diagonal {
+                    corners.concave += 1;
+                }
+            }
+        }
+
+        corners
+    }
+
+    /// Find areas completely enclosed by a component.
+    ///
+    /// Each hole is a 4-connected area of cells not belonging to the component,
+    /// which doesn't reach the bounding box edge.
+    pub fn holes(&self, label: usize) -> Vec<Component> {
+        let region = &self.regions[label];
+        let ((min_col, min_row), (width, height)) = (region.min, region.size());
+
+        // work on a window of the bounding box
+        let window = Grid {
+            content: (0..width * height)
+                .map(|i| self.labels.content[(min_col + i % width) + (min_row + i / width) * self.labels.width] == label)
+                .collect(),
+            width,
+            height,
+        };
+
+        let outside = window.components(|&a, &b| !a && !b);
+        outside.regions.into_iter()
+            .filter(|hole| {
+                let (col, row) = hole.cells[0];
+                let inside = window.content[col + row * width];
+                let touches_edge = hole.min.0 == 0 || hole.min.1 == 0 || hole.max.0 == width - 1 || hole.max.1 == height - 1;
+
+                !inside && !touches_edge
+            })
+            .map(|hole| Component {
+                cells: hole.cells.into_iter().map(|(col, row)| (col + min_col, row + min_row)).collect(),
+                min: (hole.min.0 + min_col, hole.min.1 + min_row),
+                max: (hole.max.0 + min_col, hole.max.1 + min_row),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LARGER: &str = "\
+RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+
+    const ENCLOSED: &str = "\
+AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA";
+
+    fn plants(text: &str) -> Components {
+        Grid::new(text, |chr, _| chr).components(|a, b| a == b)
+    }
+
+    /// Total (area * perimeter, area * sides) of all regions
+    fn prices(components: &Components) -> (usize, usize) {
+        (0..components.regions.len())
+            .map(|label| {
+                let area = components.regions[label].area();
+                (area * components.perimeter(label), area * components.sides(label))
+            })
+            .fold((0, 0), |acc, price| (acc.0 + price.0, acc.1 + price.1))
+    }
+
+    #[test]
+    fn fence_prices() {
+        assert_eq!(prices(&plants("AAAA\nBBCD\nBBCC\nEEEC")), (140, 80));
+        assert_eq!(prices(&plants(LARGER)), (1930, 1206));
+        assert_eq!(prices(&plants("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE")).1, 236);
+        assert_eq!(prices(&plants(ENCLOSED)).1, 368);
+    }
+
+    #[test]
+    fn corners_match_sides() {
+        for text in [LARGER, ENCLOSED] {
+            let components = plants(text);
+            for label in 0..components.regions.len() {
+                assert_eq!(components.corners(label).total(), components.sides(label));
+            }
+        }
+
+        // plus shape points out at 8 corners and into itself at 4
+        let plus = plants(".#.\n###\n.#.");
+        let label = plus.labels.at(1, 1).unwrap();
+        assert_eq!(plus.corners(label), Corners { convex: 8, concave: 4 });
+    }
+
+    #[test]
+    fn holes() {
+        let components = plants(ENCLOSED);
+        assert_eq!(components.regions.len(), 3);
+
+        let outer = components.labels.at(0, 0).unwrap();
+        let mut holes: Vec<((usize, usize), usize)> = components.holes(outer).iter()
+            .map(|hole| (hole.min, hole.area()))
+            .collect();
+        holes.sort();
+        assert_eq!(holes, [((1, 3), 4), ((3, 1), 4)]);
+
+        // regions on the border can't enclose anything
+        let inner = components.labels.at(3, 1).unwrap();
+        assert!(components.holes(inner).is_empty());
+    }
+
+    #[test]
+    fn flood_fill_and_components() {
+        let grid = Grid::new("..#\n.##\n#..", |chr, _| chr == '.');
+
+        let mut filled = grid.flood_fill((0, 0), |&open| open);
+        filled.sort();
+        assert_eq!(filled, [(0, 0), (0, 1), (1, 0)]);
+        assert!(grid.flood_fill((2, 0), |&open| open).is_empty());
+        assert!(grid.flood_fill((3, 0), |&open| open).is_empty());
+
+        let components = grid.components(|a, b| a == b);
+        assert_eq!(components.regions.len(), 4);
+        let walls = components.at(2, 0).unwrap();
+        assert_eq!((walls.area(), walls.min, walls.max), (3, (1, 0), (2, 1)));
+        assert_eq!(components.at(0, 2).unwrap().area(), 1);
+        assert_eq!(components.at(3, 0), None);
+    }
+}
@@ -0,0 +1,155 @@
+//! Text rendering of grids with overlay layers.
+//!
+//! Each overlay is a set of positions drawn with a glyph and/or an ANSI color.
+//! Later overlays are drawn on top of earlier ones.
+
+use std::{collections::HashSet, fmt, io::{self, Write}};
+
+use super::{Grid, TileChar};
+
+/// Basic ANSI terminal colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black, Red, Green, Yellow, Blue, Magenta, Cyan, White,
+}
+
+impl Color {
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+        }
+    }
+}
+
+/// How cells of an overlay are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    /// Replaces tile's own character
+    pub glyph: Option<char>,
+    pub color: Option<Color>,
+}
+
+impl Style {
+    pub fn glyph(glyph: char) -> Self {
+        Self { glyph: Some(glyph), color: None }
+    }
+
+    pub fn color(color: Color) -> Self {
+        Self { glyph: None, color: Some(color) }
+    }
+
+    pub fn with_color(self, color: Color) -> Self {
+        Self { color: Some(color), ..self }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Overlay {
+    cells: HashSet<(usize, usize)>,
+    style: Style,
+}
+
+/// Draws a grid and its overlays.
+///
+/// Implements [`Display`](fmt::Display), so rendered grid can be printed directly
+/// or turned into a string with `to_string()`.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    legend: Box<dyn Fn(&T) -> char + 'a>,
+    overlays: Vec<Overlay>,
+    ansi: bool,
+}
+
+impl<'a, T: TileChar> Renderer<'a, T> {
+    /// Creates renderer which draws tiles using their [`TileChar`] legend
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        Self::with_legend(grid, T::to_char)
+    }
+}
+
+impl<'a, T> Renderer<'a, T> {
+    /// Creates renderer which draws tiles with a custom legend
+    pub fn with_legend(grid: &'a Grid<T>, legend: impl Fn(&T) -> char + 'a) -> Self {
+        Self {
+            grid,
+            legend: Box::new(legend),
+            overlays: Vec::new(),
+            ansi: true,
+        }
+    }
+
+    /// Adds an overlay layer drawn on top of previous ones
+    pub fn overlay(mut self, cells: impl IntoIterator<Item = (usize, usize)>, style: Style) -> Self {
+        self.overlays.push(Overlay { cells: cells.into_iter().collect(), style });
+        self
+    }
+
+    /// Shorthand for overlay which only replaces characters
+    pub fn mark(self, cells: impl IntoIterator<Item = (usize, usize)>, glyph: char) -> Self {
+        self.overlay(cells, Style::glyph(glyph))
+    }
+
+    /// Shorthand for overlay which only colors cells
+    pub fn highlight(self, cells: impl IntoIterator<Item = (usize, usize)>, color: Color) -> Self {
+        self.overlay(cells, Style::color(color))
+    }
+
+    /// Toggle ANSI color output, useful when writing into files
+    pub fn ansi(mut self, enabled: bool) -> Self {
+        self.ansi = enabled;
+        self
+    }
+
+    /// Resolve final character and color of a single cell
+    fn cell(&self, pos: (usize, usize), tile: &T) -> (char, Option<Color>) {
+        let mut glyph = None;
+        let mut color = None;
+
+        // topmost overlay wins
+        for overlay in self.overlays.iter().rev() {
+            if glyph.is_some() && color.is_some() {
+                break
+            }
+            if !overlay.cells.contains(&pos) {
+                continue
+            }
+
+            glyph = glyph.or(overlay.style.glyph);
+            color = color.or(overlay.style.color);
+        }
+
+        (glyph.unwrap_or_else(|| (self.legend)(tile)), color)
+    }
+
+    /// Write rendered grid into stdout
+    pub fn print(&self) -> io::Result<()> {
+        // printing one cell at a time, take stdout lock so it's fast
+        let mut lock = io::stdout().lock();
+        write!(lock, "{self}")?;
+        lock.flush()
+    }
+}
+
+impl<T> fmt::Display for Renderer<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.grid.width();
+        for (pos, tile) in self.grid.iter_indexed() {
+            match self.cell(pos, tile) {
+                (chr, Some(color)) if self.ansi => write!(f, "\x1b[{}m{chr}\x1b[0m", color.ansi_code())?,
+                (chr, _) => write!(f, "{chr}")?,
+            }
+
+            if pos.0 + 1 == width {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
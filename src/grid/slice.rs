@@ -0,0 +1,88 @@
+//! Rows, columns and rectangular windows of grids.
+
+use std::marker::PhantomData;
+
+use super::{Grid, GridView};
+
+impl<T> Grid<T> {
+    /// Returns row as a slice, `None` if out of bounds
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row >= self.height {
+            return None
+        }
+        Some(&self.content[row * self.width..(row + 1) * self.width])
+    }
+
+    /// Returns iterator over column from top to bottom, `None` if out of bounds
+    pub fn col(&self, col: usize) -> Option<impl Iterator<Item = &T>> {
+        if col >= self.width {
+            return None
+        }
+        Some(self.content.iter().skip(col).step_by(self.width))
+    }
+
+    /// Iterate rows from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() doesn't like zero sized chunks
+        self.content.chunks(self.width.max(1))
+    }
+
+    /// Iterate columns from left to right
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.content.iter().skip(col).step_by(self.width))
+    }
+
+    /// View of a rectangular window with top-left corner at (col, row).
+    ///
+    /// Returns `None` if window doesn't fit inside grid.
+    pub fn sub_grid(&self, col: usize, row: usize, width: usize, height: usize) -> Option<SubGrid<'_, T>> {
+        SubGrid::new(self, (col, row), (width, height))
+    }
+}
+
+/// Rectangular window into a grid with its own local coordinates
+#[derive(Debug)]
+pub struct SubGrid<'a, T, G: ?Sized = Grid<T>> {
+    grid: &'a G,
+    origin: (usize, usize),
+    width: usize,
+    height: usize,
+    tile: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, G: GridView<T> + ?Sized> SubGrid<'a, T, G> {
+    /// Returns `None` if window doesn't fit inside grid
+    pub fn new(grid: &'a G, origin: (usize, usize), (width, height): (usize, usize)) -> Option<Self> {
+        if origin.0 + width > grid.width() || origin.1 + height > grid.height() {
+            return None
+        }
+        Some(Self { grid, origin, width, height, tile: PhantomData })
+    }
+
+    /// Top-left corner of window in underlying grid
+    pub fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    /// Map local position to position in underlying grid
+    pub fn to_global(&self, (col, row): (usize, usize)) -> (usize, usize) {
+        (col + self.origin.0, row + self.origin.1)
+    }
+}
+
+impl<T, G: GridView<T> + ?Sized> GridView<T> for SubGrid<'_, T, G> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, col: usize, row: usize) -> Option<&T> {
+        if col >= self.width || row >= self.height {
+            return None
+        }
+        self.grid.get(col + self.origin.0, row + self.origin.1)
+    }
+}
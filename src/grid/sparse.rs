@@ -0,0 +1,247 @@
+//! Unbounded grid backed by a hash map.
+
+use std::{collections::HashMap, fmt};
+
+use super::{Grid, TileChar};
+
+/// Sparse grid with signed coordinates, only occupied cells are stored.
+///
+/// Bounding box of occupied cells is tracked as cells are inserted and removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(isize, isize), T>,
+    /// Inclusive (min, max) corners of occupied cells
+    bounds: Option<((isize, isize), (isize, isize))>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self { cells: HashMap::new(), bounds: None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Inclusive (min, max) corners of occupied cells, `None` if empty
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.bounds
+    }
+
+    /// Width and height of bounding box
+    pub fn size(&self) -> (usize, usize) {
+        match self.bounds {
+            Some((min, max)) => ((max.0 - min.0 + 1) as usize, (max.1 - min.1 + 1) as usize),
+            None => (0, 0),
+        }
+    }
+
+    pub fn get(&self, col: isize, row: isize) -> Option<&T> {
+        self.cells.get(&(col, row))
+    }
+
+    pub fn get_mut(&mut self, col: isize, row: isize) -> Option<&mut T> {
+        self.cells.get_mut(&(col, row))
+    }
+
+    pub fn contains(&self, col: isize, row: isize) -> bool {
+        self.cells.contains_key(&(col, row))
+    }
+
+    /// Insert item, returns previous item at the position
+    pub fn insert(&mut self, col: isize, row: isize, item: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => ((min.0.min(col), min.1.min(row)), (max.0.max(col), max.1.max(row))),
+            None => ((col, row), (col, row)),
+        });
+        self.cells.insert((col, row), item)
+    }
+
+    pub fn remove(&mut self, col: isize, row: isize) -> Option<T> {
+        let removed = self.cells.remove(&(col, row))?;
+
+        // bounding box may shrink only if removed cell was on its edge
+        if let Some((min, max)) = self.bounds {
+            if col == min.0 || col == max.0 || row == min.1 || row == max.1 {
+                self.bounds = self.compute_bounds();
+            }
+        }
+
+        Some(removed)
+    }
+
+    fn compute_bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.cells.keys().fold(None, |bounds, &(col, row)| Some(match bounds {
+            Some((min, max)) => ((isize::min(min.0, col), isize::min(min.1, row)), (isize::max(max.0, col), isize::max(max.1, row))),
+            None => ((col, row), (col, row)),
+        }))
+    }
+
+    /// Iterate occupied cells in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.cells.iter().map(|(&pos, item)| (pos, item))
+    }
+
+    pub fn entry(&self, col: isize, row: isize) -> SparseEntry<'_, T> {
+        SparseEntry { grid: self, col, row }
+    }
+
+    /// Collect cells of dense grid for which `keep` holds, positions are kept as is
+    pub fn from_grid(grid: &Grid<T>, keep: impl Fn(&T) -> bool) -> Self where T: Clone {
+        grid.iter_indexed()
+            .filter(|(_, item)| keep(item))
+            .map(|((col, row), item)| ((col as isize, row as isize), item.clone()))
+            .collect()
+    }
+}
+
+impl<T: Copy> SparseGrid<T> {
+    pub fn at(&self, col: isize, row: isize) -> Option<T> {
+        self.get(col, row).copied()
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Copy bounding box into a dense grid, empty cells are set to `fill`.
+    ///
+    /// Returns grid and position of its top-left corner in sparse coordinates.
+    pub fn to_grid(&self, fill: T) -> (Grid<T>, (isize, isize)) {
+        let (width, height) = self.size();
+        let origin = self.bounds.map_or((0, 0), |(min, _)| min);
+
+        let mut content = vec![fill; width * height];
+        for (&(col, row), item) in &self.cells {
+            let (col, row) = ((col - origin.0) as usize, (row - origin.1) as usize);
+            content[col + row * width] = item.clone();
+        }
+
+        (Grid { content, width, height }, origin)
+    }
+}
+
+impl<T> FromIterator<((isize, isize), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((isize, isize), T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for ((col, row), item) in iter {
+            grid.insert(col, row, item);
+        }
+        grid
+    }
+}
+
+// draws bounding box, empty cells are shown as '.'
+impl<T: TileChar> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.bounds else { return Ok(()) };
+
+        for row in min.1..=max.1 {
+            for col in min.0..=max.0 {
+                let chr = self.get(col, row).map_or('.', TileChar::to_char);
+                write!(f, "{chr}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Helper for working with offsets in a [`SparseGrid`]
+#[derive(Debug)]
+pub struct SparseEntry<'a, T> {
+    grid: &'a SparseGrid<T>,
+    col: isize,
+    row: isize,
+}
+
+impl<T> SparseEntry<'_, T> {
+    pub fn pos(&self) -> (isize, isize) {
+        (self.col, self.row)
+    }
+
+    /// Occupied 4-connected neighbors and their positions
+    pub fn neighbors(&self) -> impl Iterator<Item = (&T, isize, isize)> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)].into_iter().filter_map(|(dc, dr)| {
+            let (col, row) = (self.col + dc, self.row + dr);
+            self.grid.get(col, row).map(|item| (item, col, row))
+        })
+    }
+}
+
+impl<'a, T> SparseEntry<'a, T> {
+    pub fn at_offset(&self, col_offset: isize, row_offset: isize) -> Option<&'a T> {
+        self.offset(col_offset, row_offset).map(|thing| thing.0)
+    }
+
+    /// Returns item at offset and its true column and row index if occupied
+    pub fn offset(&self, col_offset: isize, row_offset: isize) -> Option<(&'a T, isize, isize)> {
+        let (col, row) = (self.col + col_offset, self.row + row_offset);
+        self.grid.get(col, row).map(|thing| (thing, col, row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_follow_inserts_and_removals() {
+        let mut grid = SparseGrid::new();
+        assert_eq!((grid.bounds(), grid.size()), (None, (0, 0)));
+
+        grid.insert(2, 3, 'a');
+        grid.insert(-4, 1, 'b');
+        assert_eq!(grid.insert(0, 5, 'c'), None);
+        assert_eq!(grid.insert(0, 5, 'd'), Some('c'));
+        assert_eq!(grid.bounds(), Some(((-4, 1), (2, 5))));
+        assert_eq!(grid.size(), (7, 5));
+
+        // inner cell doesn't change anything, edge cells shrink the box
+        grid.insert(0, 2, 'e');
+        assert_eq!(grid.remove(0, 2), Some('e'));
+        assert_eq!(grid.bounds(), Some(((-4, 1), (2, 5))));
+        grid.remove(-4, 1);
+        assert_eq!(grid.bounds(), Some(((0, 3), (2, 5))));
+        assert_eq!(grid.remove(-4, 1), None);
+
+        grid.remove(2, 3);
+        grid.remove(0, 5);
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let dense: Grid<char> = Grid::parse("#..\n.#.\n..#").unwrap();
+        let sparse = SparseGrid::from_grid(&dense, |&chr| chr == '#');
+        assert_eq!(sparse.len(), 3);
+        assert_eq!(sparse.to_string(), "#..\n.#.\n..#\n");
+        assert_eq!(sparse.to_grid('.'), (dense, (0, 0)));
+
+        let shifted: SparseGrid<char> = [((-2, -1), '#'), ((-1, -1), '#')].into_iter().collect();
+        assert_eq!(shifted.to_grid('.'), (Grid::parse("##").unwrap(), (-2, -1)));
+    }
+
+    #[test]
+    fn entries() {
+        let grid: SparseGrid<u8> = [((0, 0), 1), ((1, 0), 2), ((0, -1), 3), ((5, 5), 4)].into_iter().collect();
+        let entry = grid.entry(0, 0);
+
+        let mut neighbors: Vec<(u8, isize, isize)> = entry.neighbors().map(|(&n, col, row)| (n, col, row)).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, [(2, 1, 0), (3, 0, -1)]);
+
+        assert_eq!(entry.offset(5, 5), Some((&4, 5, 5)));
+        assert_eq!(entry.at_offset(-1, 0), None);
+        assert_eq!(grid.at(0, -1), Some(3));
+    }
+}
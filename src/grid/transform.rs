@@ -0,0 +1,208 @@
+//! Rotations and reflections of grids.
+//!
+//! Every transform is available both as a zero-copy view, which only remaps
+//! indices, and as a copying version on [`Grid`].
+
+use std::marker::PhantomData;
+
+use super::{Grid, GridView};
+
+/// One of the eight symmetries of a rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    RotateCw,
+    Rotate180,
+    RotateCcw,
+    /// Mirror over main diagonal, swapping columns and rows
+    Transpose,
+    /// Mirror over anti-diagonal
+    AntiTranspose,
+    /// Mirror left and right
+    FlipH,
+    /// Mirror top and bottom
+    FlipV,
+}
+
+impl Transform {
+    pub const ALL: [Self; 8] = [
+        Self::Identity, Self::RotateCw, Self::Rotate180, Self::RotateCcw,
+        Self::Transpose, Self::AntiTranspose, Self::FlipH, Self::FlipV,
+    ];
+
+    /// Does this transform swap width and height
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Self::RotateCw | Self::RotateCcw | Self::Transpose | Self::AntiTranspose)
+    }
+
+    /// Map position in transformed grid back to position in source grid of given size
+    pub fn source_pos(self, (col, row): (usize, usize), (width, height): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Identity => (col, row),
+            Self::RotateCw => (row, height - 1 - col),
+            Self::Rotate180 => (width - 1 - col, height - 1 - row),
+            Self::RotateCcw => (width - 1 - row, col),
+            Self::Transpose => (row, col),
+            Self::AntiTranspose => (width - 1 - row, height - 1 - col),
+            Self::FlipH => (width - 1 - col, row),
+            Self::FlipV => (col, height - 1 - row),
+        }
+    }
+
+    /// Map offset (direction) in transformed grid to offset in source grid
+    pub fn source_offset(self, (dc, dr): (isize, isize)) -> (isize, isize) {
+        match self {
+            Self::Identity => (dc, dr),
+            Self::RotateCw => (dr, -dc),
+            Self::Rotate180 => (-dc, -dr),
+            Self::RotateCcw => (-dr, dc),
+            Self::Transpose => (dr, dc),
+            Self::AntiTranspose => (-dr, -dc),
+            Self::FlipH => (-dc, dr),
+            Self::FlipV => (dc, -dr),
+        }
+    }
+}
+
+/// Zero-copy transformed view into a grid, see [`Grid::transformed`]
+#[derive(Debug)]
+pub struct Transformed<'a, T, G: ?Sized = Grid<T>> {
+    grid: &'a G,
+    transform: Transform,
+    tile: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, G: GridView<T> + ?Sized> Transformed<'a, T, G> {
+    pub fn new(grid: &'a G, transform: Transform) -> Self {
+        Self { grid, transform, tile: PhantomData }
+    }
+
+    /// Map position in this view to position in underlying grid
+    pub fn source_pos(&self, pos: (usize, usize)) -> (usize, usize) {
+        self.transform.source_pos(pos, (self.grid.width(), self.grid.height()))
+    }
+}
+
+impl<T, G: GridView<T> + ?Sized> GridView<T> for Transformed<'_, T, G> {
+    fn width(&self) -> usize {
+        if self.transform.swaps_axes() { self.grid.height() } else { self.grid.width() }
+    }
+
+    fn height(&self) -> usize {
+        if self.transform.swaps_axes() { self.grid.width() } else { self.grid.height() }
+    }
+
+    fn get(&self, col: usize, row: usize) -> Option<&T> {
+        if col >= self.width() || row >= self.height() {
+            return None
+        }
+        let (col, row) = self.source_pos((col, row));
+        self.grid.get(col, row)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Zero-copy view of this grid with transform applied
+    pub fn transformed(&self, transform: Transform) -> Transformed<'_, T> {
+        Transformed::new(self, transform)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn rotate_cw(&self) -> Self {
+        self.transformed(Transform::RotateCw).to_grid()
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        self.transformed(Transform::RotateCcw).to_grid()
+    }
+
+    pub fn transpose(&self) -> Self {
+        self.transformed(Transform::Transpose).to_grid()
+    }
+
+    pub fn flip_h(&self) -> Self {
+        self.transformed(Transform::FlipH).to_grid()
+    }
+
+    pub fn flip_v(&self) -> Self {
+        self.transformed(Transform::FlipV).to_grid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(text: &str) -> Grid<char> {
+        Grid::parse(text).unwrap()
+    }
+
+    #[test]
+    fn copying_transforms() {
+        let source = grid("abc\ndef");
+
+        assert_eq!(source.rotate_cw(), grid("da\neb\nfc"));
+        assert_eq!(source.rotate_ccw(), grid("cf\nbe\nad"));
+        assert_eq!(source.transpose(), grid("ad\nbe\ncf"));
+        assert_eq!(source.flip_h(), grid("cba\nfed"));
+        assert_eq!(source.flip_v(), grid("def\nabc"));
+    }
+
+    #[test]
+    fn views() {
+        let source = grid("abc\ndef");
+        let expected = [
+            (Transform::Identity, "abc\ndef"),
+            (Transform::RotateCw, "da\neb\nfc"),
+            (Transform::Rotate180, "fed\ncba"),
+            (Transform::RotateCcw, "cf\nbe\nad"),
+            (Transform::Transpose, "ad\nbe\ncf"),
+            (Transform::AntiTranspose, "fc\neb\nda"),
+            (Transform::FlipH, "cba\nfed"),
+            (Transform::FlipV, "def\nabc"),
+        ];
+
+        for (transform, text) in expected {
+            let view = source.transformed(transform);
+            assert_eq!(view.to_grid(), grid(text), "{transform:?}");
+            assert_eq!(view.get(view.width(), 0), None);
+            assert_eq!(view.get(0, view.height()), None);
+        }
+    }
+
+    #[test]
+    fn rotations_compose() {
+        let source = grid("ab\ncd\nef");
+
+        assert_eq!(source.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), source);
+        assert_eq!(source.rotate_cw().rotate_ccw(), source);
+        assert_eq!(source.transpose().transpose(), source);
+        assert_eq!(source.rotate_cw().rotate_cw(), source.transformed(Transform::Rotate180).to_grid());
+        // views can be stacked too
+        let rotated = source.transformed(Transform::RotateCw);
+        assert_eq!(Transformed::new(&rotated, Transform::RotateCw).to_grid(), source.rotate_cw().rotate_cw());
+    }
+
+    #[test]
+    fn offsets_follow_positions() {
+        let size = (4, 3);
+        for transform in Transform::ALL {
+            let (width, height) = if transform.swaps_axes() { (size.1, size.0) } else { size };
+
+            // stepping in the view steps by source offset in the source grid
+            for (col, row) in (0..height).flat_map(|row| (0..width - 1).map(move |col| (col, row))) {
+                let here = transform.source_pos((col, row), size);
+                let right = transform.source_pos((col + 1, row), size);
+                let (dc, dr) = transform.source_offset((1, 0));
+                assert_eq!((here.0 as isize + dc, here.1 as isize + dr), (right.0 as isize, right.1 as isize), "{transform:?}");
+            }
+            for (col, row) in (0..height - 1).flat_map(|row| (0..width).map(move |col| (col, row))) {
+                let here = transform.source_pos((col, row), size);
+                let below = transform.source_pos((col, row + 1), size);
+                let (dc, dr) = transform.source_offset((0, 1));
+                assert_eq!((here.0 as isize + dc, here.1 as isize + dr), (below.0 as isize, below.1 as isize), "{transform:?}");
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::{io, path::{Path, PathBuf}};
 
+pub mod cycle;
+pub mod dot;
 pub mod grid;
+pub mod search;
 
 pub fn read_input() -> io::Result<String> {
     let filename = get_filename_from_args()?;
@@ -9,10 +12,21 @@ pub fn read_input() -> io::Result<String> {
     std::fs::read_to_string(resolved)
 }
 
+/// Check if given flag (e.g. `--render`) was passed after the input file
+pub fn has_flag(flag: &str) -> bool {
+    std::env::args().skip(2).any(|arg| arg == flag)
+}
+
+/// Get value following given flag (e.g. `--export out.png`) after the input file
+pub fn flag_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip(2);
+    args.find(|arg| arg == flag)?;
+    args.next()
+}
+
 fn get_filename_from_args() -> Result<String, io::Error> {
     std::env::args().nth(1)
-        .ok_or_else(|| io::Error::new(
-            io::ErrorKind::Other,
+        .ok_or_else(|| io::Error::other(
             r#"expected input file path or "-" as first argument"#
         ))
 }
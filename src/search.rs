@@ -0,0 +1,494 @@
+//! Generic graph searches over user-defined states.
+//!
+//! States only need to be hashable, graph is described by a successor function.
+//! Every search returns the cost of the found path, the path itself and some statistics.
+
+use std::{collections::{hash_map::Entry, HashMap, VecDeque}, hash::Hash};
+
+pub mod queue;
+
+use queue::IndexedHeap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Number of states taken out of the frontier and expanded
+    pub expanded: usize,
+    /// Number of states pushed into the frontier
+    pub pushed: usize,
+    /// Largest size of the frontier during search
+    pub max_frontier: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S> {
+    pub cost: usize,
+    /// States from start to goal, both inclusive
+    pub path: Vec<S>,
+    pub stats: Stats,
+}
+
+/// Discovered states and their parents, indexed by discovery order
+struct Explored<S> {
+    index: HashMap<S, usize>,
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    costs: Vec<usize>,
+}
+
+impl<S: Clone + Eq + Hash> Explored<S> {
+    fn new() -> Self {
+        Self { index: HashMap::new(), states: Vec::new(), parents: Vec::new(), costs: Vec::new() }
+    }
+
+    /// Record state if it's new or reached with a smaller cost, returns its index if so
+    fn relax(&mut self, state: S, parent: Option<usize>, cost: usize) -> Option<usize> {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => {
+                let i = *entry.get();
+                if cost >= self.costs[i] {
+                    return None
+                }
+                self.parents[i] = parent;
+                self.costs[i] = cost;
+                Some(i)
+            },
+            Entry::Vacant(entry) => {
+                let i = self.states.len();
+                self.states.push(entry.key().clone());
+                entry.insert(i);
+                self.parents.push(parent);
+                self.costs.push(cost);
+                Some(i)
+            },
+        }
+    }
+
+    /// Walk parent links back to start
+    fn path_to(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].clone()];
+        while let Some(parent) = self.parents[i] {
+            path.push(self.states[parent].clone());
+            i = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search, every step costs 1.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut stats = Stats::default();
+    let mut explored = Explored::new();
+    let mut queue = VecDeque::new();
+
+    queue.push_back(explored.relax(start, None, 0)?);
+    stats.pushed += 1;
+
+    while let Some(i) = queue.pop_front() {
+        stats.expanded += 1;
+
+        if goal(&explored.states[i]) {
+            return Some(SearchResult { cost: explored.costs[i], path: explored.path_to(i), stats })
+        }
+
+        let cost = explored.costs[i] + 1;
+        for next in successors(&explored.states[i]) {
+            // first visit is always the shortest in bfs
+            if explored.index.contains_key(&next) {
+                continue
+            }
+            if let Some(j) = explored.relax(next, Some(i), cost) {
+                queue.push_back(j);
+                stats.pushed += 1;
+            }
+        }
+        stats.max_frontier = stats.max_frontier.max(queue.len());
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm, successors are yielded with the cost of moving into them.
+pub fn dijkstra<S, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(start, successors, |_| 0, goal)
+}
+
+/// A* search, `heuristic` must never overestimate remaining cost to goal.
+pub fn astar<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut stats = Stats::default();
+    let mut explored = Explored::new();
+    // state indices prioritized by (estimated total cost, cost so far)
+    let mut frontier = IndexedHeap::new();
+
+    let h = heuristic(&start);
+    frontier.push(explored.relax(start, None, 0)?, (h, 0));
+    stats.pushed += 1;
+
+    while let Some((i, (_, cost))) = frontier.pop() {
+        stats.expanded += 1;
+
+        if goal(&explored.states[i]) {
+            return Some(SearchResult { cost, path: explored.path_to(i), stats })
+        }
+
+        for (next, step) in successors(&explored.states[i]) {
+            // paths whose cost doesn't fit can't be the cheapest
+            let Some(next_cost) = cost.checked_add(step) else { continue };
+            let h = heuristic(&next);
+            if let Some(j) = explored.relax(next, Some(i), next_cost) {
+                frontier.push(j, (next_cost.saturating_add(h), next_cost));
+                stats.pushed += 1;
+            }
+        }
+        stats.max_frontier = stats.max_frontier.max(frontier.len());
+    }
+
+    None
+}
+
+/// Every optimal path of a search, stored as a predecessor DAG.
+///
+/// Instead of copying paths around, each state only remembers which states
+/// led into it with the optimal cost.
+#[derive(Debug, Clone)]
+pub struct AllPaths<S> {
+    pub cost: usize,
+    pub stats: Stats,
+    states: Vec<S>,
+    preds: Vec<Vec<usize>>,
+    goals: Vec<usize>,
+}
+
+impl<S: Clone + Eq + Hash> AllPaths<S> {
+    /// Goal states reached with the optimal cost
+    pub fn goals(&self) -> impl Iterator<Item = &S> {
+        self.goals.iter().map(|&i| &self.states[i])
+    }
+
+    /// Every state which lies on at least one optimal path
+    pub fn on_optimal_paths(&self) -> impl Iterator<Item = &S> {
+        let mut seen = vec![false; self.states.len()];
+        let mut stack = self.goals.clone();
+        for &goal in &self.goals {
+            seen[goal] = true;
+        }
+
+        // walk predecessor links back to start
+        while let Some(i) = stack.pop() {
+            for &pred in &self.preds[i] {
+                if !seen[pred] {
+                    seen[pred] = true;
+                    stack.push(pred);
+                }
+            }
+        }
+
+        self.states.iter().zip(seen).filter_map(|(state, seen)| seen.then_some(state))
+    }
+
+    /// Enumerate at most `k` distinct optimal paths, from start to goal
+    pub fn paths(&self, k: usize) -> Vec<Vec<S>> {
+        let mut found = Vec::new();
+        if k == 0 {
+            return found
+        }
+
+        for &goal in &self.goals {
+            // backtracking walk from goal towards start,
+            // each state remembers which of its predecessors to try next
+            let mut path: Vec<(usize, usize)> = vec![(goal, 0)];
+
+            while let Some(&(state, next)) = path.last() {
+                if self.preds[state].is_empty() {
+                    // reached start
+                    found.push(path.iter().rev().map(|&(i, _)| self.states[i].clone()).collect());
+                    if found.len() == k {
+                        return found
+                    }
+                    path.pop();
+                    continue
+                }
+
+                match self.preds[state].get(next) {
+                    Some(&pred) => {
+                        path.last_mut().unwrap().1 += 1;
+                        path.push((pred, 0));
+                    },
+                    None => {
+                        path.pop();
+                    },
+                }
+            }
+        }
+
+        found
+    }
+}
+
+/// Dijkstra's algorithm which keeps searching until every optimal path to a goal is found.
+pub fn dijkstra_all<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<AllPaths<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut stats = Stats::default();
+    let mut explored = Explored::new();
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut frontier = IndexedHeap::new();
+
+    let mut best: Option<usize> = None;
+    let mut goals = Vec::new();
+
+    frontier.push(explored.relax(start, None, 0)?, 0);
+    stats.pushed += 1;
+
+    while let Some((i, cost)) = frontier.pop() {
+        // everything left is more expensive than found paths
+        if best.is_some_and(|best| cost > best) {
+            break
+        }
+        stats.expanded += 1;
+
+        if goal(&explored.states[i]) {
+            best = Some(cost);
+            goals.push(i);
+            continue
+        }
+
+        for (next, step) in successors(&explored.states[i]) {
+            let Some(next_cost) = cost.checked_add(step) else { continue };
+
+            // another optimal way into an already discovered state
+            if let Some(&j) = explored.index.get(&next) {
+                if next_cost == explored.costs[j] {
+                    // successors may yield the same state more than once
+                    if !preds[j].contains(&i) {
+                        preds[j].push(i);
+                    }
+                    continue
+                }
+            }
+
+            if let Some(j) = explored.relax(next, Some(i), next_cost) {
+                if j == preds.len() {
+                    preds.push(Vec::new());
+                }
+                preds[j] = vec![i];
+
+                frontier.push(j, next_cost);
+                stats.pushed += 1;
+            }
+        }
+        stats.max_frontier = stats.max_frontier.max(frontier.len());
+    }
+
+    Some(AllPaths {
+        cost: best?,
+        stats,
+        states: explored.states,
+        preds,
+        goals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::grid::{cursor::Direction, Grid};
+
+    const MAZE: &str = "\
+...#......
+.#.#.####.
+.#...#....
+.####.#.#.
+......#.#.
+.######.#.
+...#....#.
+.#.#.####.
+.#...#....
+...#...#..";
+
+    fn maze() -> Grid<bool> {
+        Grid::new(MAZE, |chr, _| chr == '.')
+    }
+
+    type Pos = (usize, usize);
+
+    /// Open 4-neighbors, entering a cell costs 1 plus its column modulo 3
+    fn neighbors(grid: &Grid<bool>, &(col, row): &Pos) -> Vec<(Pos, usize)> {
+        let here = grid.entry(col, row);
+        [(1, 0), (-1, 0), (0, 1), (0, -1)].into_iter()
+            .filter_map(|(dc, dr)| match here.offset(dc, dr) {
+                Some((true, col, row)) => Some(((col, row), 1 + col % 3)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = maze();
+        let goal: Pos = (9, 9);
+        // every step costs at least 1
+        let manhattan = |&(col, row): &Pos| goal.0.abs_diff(col) + goal.1.abs_diff(row);
+
+        let plain = dijkstra((0, 0), |pos| neighbors(&grid, pos), |&pos| pos == goal).unwrap();
+        let guided = astar((0, 0), |pos| neighbors(&grid, pos), manhattan, |&pos| pos == goal).unwrap();
+
+        assert_eq!(guided.cost, plain.cost);
+        assert!(guided.stats.expanded <= plain.stats.expanded);
+
+        // path is walkable and costs what was reported
+        assert_eq!(guided.path.first(), Some(&(0, 0)));
+        assert_eq!(guided.path.last(), Some(&goal));
+        let cost: usize = guided.path.windows(2)
+            .map(|pair| neighbors(&grid, &pair[0]).into_iter().find(|(pos, _)| *pos == pair[1]).unwrap().1)
+            .sum();
+        assert_eq!(cost, guided.cost);
+    }
+
+    #[test]
+    fn unit_costs_match_bfs() {
+        let grid = maze();
+        let unit = |pos: &Pos| neighbors(&grid, pos).into_iter().map(|(next, _)| (next, 1));
+        let steps = |pos: &Pos| neighbors(&grid, pos).into_iter().map(|(next, _)| next);
+
+        for goal in [(9, 9), (4, 4), (0, 9), (7, 6)] {
+            let found = bfs((0, 0), steps, |&pos| pos == goal).unwrap();
+            assert_eq!(dijkstra((0, 0), unit, |&pos| pos == goal).unwrap().cost, found.cost);
+            assert_eq!(found.path.len(), found.cost + 1);
+        }
+    }
+
+    const REINDEER: &str = "\
+#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+
+    /// Every optimal reindeer run through the maze, moving costs 1 and turning 1000
+    fn reindeer_runs(text: &str) -> AllPaths<(Pos, Direction)> {
+        let grid = Grid::new(text, |chr, _| chr);
+        let start = grid.find_one_pos_by(|&chr| chr == 'S').unwrap();
+
+        let successors = |&(pos, dir): &(Pos, Direction)| {
+            let mut next = vec![((pos, dir.turn_left()), 1000), ((pos, dir.turn_right()), 1000)];
+            let (dc, dr) = dir.offset();
+            if let Some((&tile, col, row)) = grid.entry(pos.0, pos.1).offset(dc, dr) {
+                if tile != '#' {
+                    next.push((((col, row), dir), 1));
+                }
+            }
+            next
+        };
+
+        dijkstra_all((start, Direction::Right), successors, |&(pos, _)| grid.get(pos.0, pos.1) == Some(&'E')).unwrap()
+    }
+
+    #[test]
+    fn all_optimal_paths() {
+        let runs = reindeer_runs(REINDEER);
+        assert_eq!(runs.cost, 11048);
+        assert_eq!(runs.goals().count(), 1);
+
+        let tiles: HashSet<Pos> = runs.on_optimal_paths().map(|&(pos, _)| pos).collect();
+        assert_eq!(tiles.len(), 64);
+
+        // enumerated paths cover exactly the same tiles and all cost the same
+        let paths = runs.paths(usize::MAX);
+        assert!(paths.len() > 1);
+        let covered: HashSet<Pos> = paths.iter().flatten().map(|&(pos, _)| pos).collect();
+        assert_eq!(covered, tiles);
+
+        for path in &paths {
+            let moves = path.windows(2).filter(|pair| pair[0].0 != pair[1].0).count();
+            let turns = path.len() - 1 - moves;
+            assert_eq!(moves + 1000 * turns, runs.cost);
+        }
+        assert_eq!(runs.paths(1).len(), 1);
+        assert!(runs.paths(0).is_empty());
+    }
+
+    #[test]
+    fn repeated_successors_give_distinct_paths() {
+        // diamond where every edge is yielded twice
+        let successors = |&state: &u8| -> Vec<(u8, usize)> {
+            let next: &[u8] = match state {
+                0 => &[1, 2],
+                1 | 2 => &[3],
+                _ => &[],
+            };
+            next.iter().flat_map(|&next| [(next, 1), (next, 1)]).collect()
+        };
+
+        let found = dijkstra_all(0, successors, |&state| state == 3).unwrap();
+        assert_eq!(found.cost, 2);
+
+        let mut paths = found.paths(10);
+        paths.sort();
+        assert_eq!(paths, [vec![0, 1, 3], vec![0, 2, 3]]);
+    }
+
+    #[test]
+    fn overflowing_cost_is_skipped() {
+        let successors = |&state: &u8| match state {
+            0 => vec![(1, usize::MAX), (3, 1)],
+            1 => vec![(2, 1)],
+            _ => vec![],
+        };
+
+        assert!(dijkstra(0, successors, |&state| state == 2).is_none());
+        assert!(dijkstra_all(0, successors, |&state| state == 2).is_none());
+        assert_eq!(dijkstra(0, successors, |&state| state == 1).unwrap().cost, usize::MAX);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let grid = maze();
+        assert!(dijkstra((0, 0), |pos| neighbors(&grid, pos), |&pos| pos == (3, 0)).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+//! Indexed min-heap with decrease-key.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Binary min-heap which also tracks where each key is stored.
+///
+/// Every key is in the heap at most once, so membership tests are `O(1)`
+/// and lowering priority of a queued key is `O(log n)`.
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K, P> {
+    heap: Vec<(K, P)>,
+    index: HashMap<K, usize>,
+}
+
+impl<K: Clone + Eq + Hash, P: Ord> Default for IndexedHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, P: Ord> IndexedHeap<K, P> {
+    pub fn new() -> Self {
+        Self { heap: Vec::new(), index: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.index.get(key).map(|&i| &self.heap[i].1)
+    }
+
+    /// Insert key, or lower its priority if it's already queued.
+    ///
+    /// Returns `false` if key was queued with same or lower priority already.
+    pub fn push(&mut self, key: K, priority: P) -> bool {
+        match self.index.get(&key) {
+            Some(&i) if priority < self.heap[i].1 => {
+                self.heap[i].1 = priority;
+                self.sift_up(i);
+                true
+            },
+            Some(_) => false,
+            None => {
+                let i = self.heap.len();
+                self.index.insert(key.clone(), i);
+                self.heap.push((key, priority));
+                self.sift_up(i);
+                true
+            },
+        }
+    }
+
+    /// Remove key with lowest priority
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None
+        }
+
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (key, priority) = self.heap.pop()?;
+        self.index.remove(&key);
+        self.sift_down(0);
+
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        *self.index.get_mut(&self.heap[a].0).unwrap() = a;
+        *self.index.get_mut(&self.heap[b].0).unwrap() = b;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap[i].1 >= self.heap[parent].1 {
+                break
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let (left, right) = (2 * i + 1, 2 * i + 2);
+            let mut smallest = i;
+
+            if left < self.heap.len() && self.heap[left].1 < self.heap[smallest].1 {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.heap[right].1 < self.heap[smallest].1 {
+                smallest = right;
+            }
+            if smallest == i {
+                break
+            }
+
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_in_priority_order() {
+        let mut heap = IndexedHeap::new();
+        for (key, priority) in [('a', 5), ('b', 1), ('c', 4), ('d', 2), ('e', 3)] {
+            assert!(heap.push(key, priority));
+        }
+
+        let popped: Vec<char> = std::iter::from_fn(|| heap.pop().map(|(key, _)| key)).collect();
+        assert_eq!(popped, ['b', 'd', 'e', 'c', 'a']);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn decrease_key() {
+        let mut heap = IndexedHeap::new();
+        heap.push('a', 1);
+        heap.push('b', 5);
+        heap.push('c', 3);
+
+        // higher priority is ignored, lower one moves key up
+        assert!(!heap.push('b', 7));
+        assert!(heap.push('b', 0));
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.priority(&'b'), Some(&0));
+
+        assert_eq!(heap.pop(), Some(('b', 0)));
+        assert!(!heap.contains(&'b'));
+        assert!(heap.contains(&'c'));
+        assert_eq!(heap.pop(), Some(('a', 1)));
+        assert_eq!(heap.pop(), Some(('c', 3)));
+        assert_eq!(heap.pop(), None);
+    }
+}